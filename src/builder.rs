@@ -0,0 +1,129 @@
+//! Incremental hashing.
+
+use std::io;
+
+use {raw, Hash};
+
+/// An incremental builder of hashes.
+///
+/// This gives the same hash as [`Hash::new`](./struct.Hash.html#method.new) over the concatenation
+/// of the written bytes, which need not be split at character boundaries.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::{Hash, HashBuilder};
+///
+/// let mut builder = HashBuilder::new();
+/// builder.write(b"comp");
+/// builder.write(b"uter");
+/// assert_eq!(builder.finish(), Hash::new("computer"));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct HashBuilder {
+    /// The first byte, if any.
+    first: Option<u8>,
+    /// The trailing phones.
+    res: u64,
+    /// The phone budget, which is zero when it is exhausted.
+    n: u8,
+}
+
+impl Default for HashBuilder {
+    #[inline]
+    fn default() -> HashBuilder {
+        HashBuilder::new()
+    }
+}
+
+impl HashBuilder {
+    /// Create a builder of the empty string.
+    #[inline]
+    pub fn new() -> HashBuilder {
+        HashBuilder {
+            first: None,
+            res: 0,
+            n: 1,
+        }
+    }
+
+    /// Feed a byte.
+    #[inline]
+    pub fn push(&mut self, x: u8) {
+        if self.first.is_none() {
+            self.first = Some(x);
+        } else if self.n != 0 {
+            if let Some(x) = raw::filter(self.res as u8, x) {
+                self.res <<= 8;
+                self.res |= x as u64;
+                self.n <<= 1;
+            }
+        }
+    }
+
+    /// Feed some bytes.
+    #[inline]
+    pub fn write(&mut self, bytes: &[u8]) {
+        for &x in bytes {
+            self.push(x);
+        }
+    }
+
+    /// Get the hash of the bytes fed so far.
+    #[inline]
+    pub fn finish(&self) -> Hash {
+        let first_byte = raw::map_first(self.first.unwrap_or(0)) as u64;
+
+        Hash {
+            hash: self.res | (first_byte << 56),
+        }
+    }
+}
+
+/// A hashing adapter for `std::io`.
+///
+/// This feeds the written bytes into a [`HashBuilder`](./struct.HashBuilder.html).
+///
+/// # Examples
+///
+/// ```rust
+/// use std::io;
+/// use eudex::{Hash, HashWriter};
+///
+/// let mut writer = HashWriter::new();
+/// io::copy(&mut &b"computer"[..], &mut writer).unwrap();
+/// assert_eq!(writer.finish(), Hash::new("computer"));
+/// ```
+#[derive(Copy, Clone, Debug, Default)]
+pub struct HashWriter {
+    builder: HashBuilder,
+}
+
+impl HashWriter {
+    /// Create a writer of the empty string.
+    #[inline]
+    pub fn new() -> HashWriter {
+        HashWriter {
+            builder: HashBuilder::new(),
+        }
+    }
+
+    /// Get the hash of the bytes written so far.
+    #[inline]
+    pub fn finish(&self) -> Hash {
+        self.builder.finish()
+    }
+}
+
+impl io::Write for HashWriter {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.builder.write(buf);
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+//! Caching of hashes.
+
+use std::num::NonZeroUsize;
+
+use lru::LruCache;
+
+use {Hash, Lang};
+
+/// A hasher, which caches the hashes of the most recently hashed words.
+///
+/// Hashing a word with [`Hash::new`](./struct.Hash.html#method.new) is cheap, usually cheaper
+/// than the cache lookup itself, so caching is only worthwhile when the words are preprocessed
+/// first, e.g. in a [language mode](./enum.Lang.html), and the same words recur, as in
+/// interactive search.
+///
+/// This requires the `lru` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::{CachingHasher, Hash, Lang};
+///
+/// let mut hasher = CachingHasher::with_lang(1000, Lang::CentralEuropean);
+/// assert_eq!(hasher.hash("Szabó"), Hash::new_lang("Szabó", Lang::CentralEuropean));
+/// assert_eq!(hasher.hash("Szabó"), Hash::new_lang("Szabó", Lang::CentralEuropean));
+/// ```
+#[derive(Debug)]
+pub struct CachingHasher {
+    /// The hashes by word.
+    cache: LruCache<String, Hash>,
+    /// The language mode, if any.
+    lang: Option<Lang>,
+}
+
+impl CachingHasher {
+    /// Create a hasher caching up to some number of words.
+    ///
+    /// The words are hashed with [`Hash::new`](./struct.Hash.html#method.new).
+    ///
+    /// # Panics
+    ///
+    /// This panics if the capacity is zero.
+    pub fn new(capacity: usize) -> CachingHasher {
+        CachingHasher {
+            cache: LruCache::new(NonZeroUsize::new(capacity).expect("the capacity must be non-zero")),
+            lang: None,
+        }
+    }
+
+    /// Create a hasher caching up to some number of words in some language mode.
+    ///
+    /// The words are hashed with [`Hash::new_lang`](./struct.Hash.html#method.new_lang).
+    ///
+    /// # Panics
+    ///
+    /// This panics if the capacity is zero.
+    pub fn with_lang(capacity: usize, lang: Lang) -> CachingHasher {
+        CachingHasher {
+            lang: Some(lang),
+            ..CachingHasher::new(capacity)
+        }
+    }
+
+    /// Hash a word, using the cached hash if present.
+    pub fn hash(&mut self, word: &str) -> Hash {
+        if let Some(&hash) = self.cache.get(word) {
+            return hash;
+        }
+
+        let hash = match self.lang {
+            Some(lang) => Hash::new_lang(word, lang),
+            None => Hash::new(word),
+        };
+        self.cache.put(word.to_owned(), hash);
+
+        hash
+    }
+
+    /// The number of cached words.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Is the cache empty?
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+}
@@ -0,0 +1,430 @@
+//! Configurable hashing.
+
+use std::borrow::Cow;
+use std::{error, fmt, iter};
+
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize};
+
+use raw::PhoneMap;
+use {phrase, raw, Hash, Lang};
+
+/// A character, which the phone tables cannot map.
+///
+/// This is returned by the strict hashing of [`HashConfig`](./struct.HashConfig.html).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct UnmappedChar(pub char);
+
+impl fmt::Display for UnmappedChar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the character {:?} (U+{:04X}) cannot be mapped to a phone", self.0, self.0 as u32)
+    }
+}
+
+impl error::Error for UnmappedChar {}
+
+/// A configuration of the hashing procedure.
+///
+/// The default configuration gives the same hashes as [`Hash::new`](./struct.Hash.html#method.new).
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::{Hash, HashConfig};
+///
+/// let config = HashConfig::new().vowel_length(true);
+/// assert!(config.build_hash("tuli") != config.build_hash("tuuli"));
+/// assert_eq!(HashConfig::new().build_hash("tuli"), Hash::new("tuli"));
+/// ```
+///
+/// # Serialization
+///
+/// With the `serde` feature, the configuration can be serialized and deserialized, e.g. to load
+/// a profile from a file. Every option is named as its builder method, and missing options take
+/// their default. Unknown options, phone tables of the wrong length, and a `fold_after` outside 1
+/// to 8 are rejected.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct HashConfig {
+    /// Mark repeated vowels as long instead of collapsing them.
+    vowel_length: bool,
+    /// Mark the length of runs of repeated vowels, quantized to three buckets.
+    vowel_runs: bool,
+    /// Nudge the previous phone instead of dropping repeated ones.
+    soft: bool,
+    /// Split words and camel case into words, each starting at a boundary.
+    split_camel_case: bool,
+    /// The number of phones, after which the following phones are folded into them.
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_fold_after"))]
+    fold_after: Option<u8>,
+    /// Reject characters, which cannot be mapped, in `try_build_hash`.
+    strict: bool,
+    /// Skip the letter h.
+    silent_h: bool,
+    /// The language mode, if any.
+    lang: Option<Lang>,
+    /// The phone tables.
+    tables: raw::Tables,
+}
+
+impl HashConfig {
+    /// Create the default configuration.
+    #[inline]
+    pub fn new() -> HashConfig {
+        HashConfig::default()
+    }
+
+    /// Treat repeated vowels as length markers.
+    ///
+    /// By default, a run of the same vowel collapses into a single phone, so "tuli" and "tuuli"
+    /// are equal. For languages where vowel length is phonemic (e.g. Finnish or romanized
+    /// Japanese), enabling this sets [`raw::VOWEL_LENGTH`](./raw/constant.VOWEL_LENGTH.html) on
+    /// the phone of a vowel when it is immediately repeated, making such words differ. A run of
+    /// the same vowel is marked once, regardless of its length. Only vowels, whose phone is kept
+    /// by the hash, are marked, so a run dropped as a duplicate of the previous phone (e.g. the
+    /// "aa" in "taali", following the t) is not.
+    #[inline]
+    pub fn vowel_length(mut self, enable: bool) -> HashConfig {
+        self.vowel_length = enable;
+        self
+    }
+
+    /// Encode the length of runs of repeated vowels.
+    ///
+    /// This is a finer variant of [`vowel_length`](#method.vowel_length), for languages where
+    /// more than two vowel lengths are distinguished (e.g. Estonian). The length of a run of the
+    /// same vowel is quantized into three buckets, which are marked on the last phone:
+    ///
+    /// - A single vowel (e.g. "tuli") is not marked.
+    /// - A vowel repeated once (e.g. "tuuli") gets [`raw::VOWEL_LENGTH`](./raw/constant.VOWEL_LENGTH.html).
+    /// - A vowel repeated twice or more (e.g. "tuuuli" or "tuuuuli") gets
+    ///   [`raw::VOWEL_OVERLONG`](./raw/constant.VOWEL_OVERLONG.html).
+    ///
+    /// As with `vowel_length`, only vowels whose phone is kept by the hash are marked.
+    ///
+    /// Enabling this implies `vowel_length`, and is likewise overridden by `soft`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::HashConfig;
+    ///
+    /// let config = HashConfig::new().vowel_runs(true);
+    /// assert!(config.build_hash("tuuli") != config.build_hash("tuuuli"));
+    /// assert_eq!(config.build_hash("tuuuli"), config.build_hash("tuuuuli"));
+    /// ```
+    #[inline]
+    pub fn vowel_runs(mut self, enable: bool) -> HashConfig {
+        self.vowel_runs = enable;
+        self
+    }
+
+    /// Let repeated phones nudge the hash.
+    ///
+    /// By default, a phone whose discriminant repeats the previous one is dropped, so "a" and
+    /// "aaa" are equal. Enabling this uses [`raw::filter_soft`](./raw/fn.filter_soft.html), which
+    /// perturbs the previous phone instead, such that repetitions change the last byte slightly.
+    /// As repeated vowels are no longer dropped, this takes precedence over `vowel_length`.
+    #[inline]
+    pub fn soft(mut self, enable: bool) -> HashConfig {
+        self.soft = enable;
+        self
+    }
+
+    /// Treat camel case, as well as whitespace and punctuation, as word boundaries.
+    ///
+    /// By default, everything but letters is skipped, and the string is hashed as a single word,
+    /// whose phones are deduplicated across the words. Enabling this splits the string into
+    /// [`tokens`](./phrase/fn.tokens.html), which are further split at lowercase to uppercase
+    /// transitions (see [`split_camel_case`](./phrase/fn.split_camel_case.html)). The words are
+    /// then combined into one hash, where the first letter of every following word starts a new
+    /// word, such that its phone is always kept. This makes e.g. "NewYork", "new york", and
+    /// "new-york" equal.
+    #[inline]
+    pub fn split_camel_case(mut self, enable: bool) -> HashConfig {
+        self.split_camel_case = enable;
+        self
+    }
+
+    /// Fold the phones after some number of phones into the earlier ones.
+    ///
+    /// By default, the first letter is followed by at most eight phones, and the rest of the word
+    /// is dropped, so long words which differ only in their tails are equal. Setting this to `n`
+    /// keeps `n` phones, after which every phone is XORed into the slot of the phone `n` phones
+    /// earlier, such that the whole word contributes to the hash. Lower values make the hash
+    /// sensitive to the tail at the cost of discriminating the first phones less.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `n` is not between 1 and 8.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::{Hash, HashConfig};
+    ///
+    /// assert_eq!(Hash::new("internationalization"), Hash::new("internationalizing"));
+    ///
+    /// let config = HashConfig::new().fold_after(6);
+    /// assert!(config.build_hash("internationalization") != config.build_hash("internationalizing"));
+    /// ```
+    #[inline]
+    pub fn fold_after(mut self, n: u8) -> HashConfig {
+        assert!((1..=8).contains(&n), "the number of phones must be between 1 and 8");
+
+        self.fold_after = Some(n);
+        self
+    }
+
+    /// Preprocess the strings in a language mode.
+    ///
+    /// See [`Lang::preprocess`](./enum.Lang.html#method.preprocess).
+    #[inline]
+    pub fn lang(mut self, lang: Lang) -> HashConfig {
+        self.lang = Some(lang);
+        self
+    }
+
+    /// Reject characters, which cannot be mapped to phones.
+    ///
+    /// The phone tables only map ASCII, and other characters are skipped silently, which can give
+    /// misleading hashes. When strict, [`try_build_hash`](#method.try_build_hash) instead returns
+    /// an error for the first non-ASCII character, which is left after the preprocessing of the
+    /// language mode (if any). For instance, the Central European mode maps the Latin-extended
+    /// letters, whereas other scripts are still rejected. Other ASCII characters than letters
+    /// (e.g. whitespace) are accepted and skipped. `build_hash` is lenient regardless.
+    #[inline]
+    pub fn strict(mut self, enable: bool) -> HashConfig {
+        self.strict = enable;
+        self
+    }
+
+    /// Treat the letter h as silent.
+    ///
+    /// By default, h is a fricative, so e.g. "hour" and "our" are far apart, as the h is the first
+    /// letter. Enabling this skips every h (after the preprocessing of the language mode, if any),
+    /// as in languages where it is mute (e.g. French, Spanish, or Italian). Note that this also
+    /// affects h in digraphs (e.g. "th" is hashed as "t").
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::{Hash, HashConfig};
+    ///
+    /// let config = HashConfig::new().silent_h(true);
+    /// assert!(Hash::new("hour") != Hash::new("our"));
+    /// assert_eq!(config.build_hash("hour"), config.build_hash("our"));
+    /// ```
+    #[inline]
+    pub fn silent_h(mut self, enable: bool) -> HashConfig {
+        self.silent_h = enable;
+        self
+    }
+
+    /// Use custom phone tables.
+    ///
+    /// See [`raw::TableBuilder`](./raw/struct.TableBuilder.html).
+    #[inline]
+    pub fn tables(mut self, tables: raw::Tables) -> HashConfig {
+        self.tables = tables;
+        self
+    }
+
+    /// Phonetically hash this string under the configuration.
+    pub fn build_hash(&self, string: &str) -> Hash {
+        self.build_preprocessed(&self.tables, &self.preprocess(string))
+    }
+
+    /// Turn the configuration into a reusable hasher.
+    ///
+    /// The state derived from the configuration is computed once, rather than on every hash,
+    /// which pays off when hashing many words under a fixed configuration. See
+    /// [`ConfiguredHasher`](./struct.ConfiguredHasher.html).
+    pub fn into_hasher(self) -> ConfiguredHasher {
+        ConfiguredHasher {
+            tables: raw::CompiledTables::new(&self.tables),
+            config: self,
+        }
+    }
+
+    /// Phonetically hash this string under the configuration, rejecting unmapped characters if
+    /// strict.
+    ///
+    /// If the configuration is not [strict](#method.strict), this never fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::{HashConfig, Lang, UnmappedChar};
+    ///
+    /// let config = HashConfig::new().strict(true);
+    /// assert_eq!(config.try_build_hash("Szabó"), Err(UnmappedChar('ó')));
+    /// assert!(config.lang(Lang::CentralEuropean).try_build_hash("Szabó").is_ok());
+    /// ```
+    pub fn try_build_hash(&self, string: &str) -> Result<Hash, UnmappedChar> {
+        let string = self.preprocess(string);
+
+        if self.strict {
+            if let Some(x) = string.chars().find(|x| !x.is_ascii()) {
+                return Err(UnmappedChar(x));
+            }
+        }
+
+        Ok(self.build_preprocessed(&self.tables, &string))
+    }
+
+    /// Preprocess a string in the language mode, and skip silent h.
+    fn preprocess<'a>(&self, string: &'a str) -> Cow<'a, str> {
+        let string = match self.lang {
+            Some(lang) => Cow::Owned(lang.preprocess(string)),
+            None => Cow::Borrowed(string),
+        };
+
+        if self.silent_h && string.contains(['h', 'H']) {
+            Cow::Owned(string.replace(['h', 'H'], ""))
+        } else {
+            string
+        }
+    }
+
+    /// Hash a preprocessed string.
+    fn build_preprocessed<T: PhoneMap>(&self, tables: &T, string: &str) -> Hash {
+        if self.split_camel_case {
+            self.combine(tables, phrase::tokens(string).flat_map(phrase::split_camel_case))
+        } else {
+            self.combine(tables, iter::once(string))
+        }
+    }
+
+    /// Hash the concatenation of some words, where every word but the first starts at a boundary.
+    fn combine<'a, T: PhoneMap, I: Iterator<Item = &'a str>>(&self, tables: &T, words: I) -> Hash {
+        // The bytes, and whether they start a word.
+        let mut bytes = words.flat_map(|word| word.bytes().enumerate().map(|(i, x)| (i == 0, x)));
+        let first = bytes.next().map_or(0, |(_, x)| x);
+
+        let first_byte = tables.map_first(first) as u64;
+
+        let mut res = 0u64;
+        // The number of accepted phones.
+        let mut pushed = 0;
+        // The last accepted phone, and the shift of the byte holding it.
+        let mut last = 0u8;
+        let mut shift = 0;
+        let mut prev = first | 32;
+        // The number of repetitions in the current run of vowels, if its phone was pushed.
+        let mut run = None;
+
+        for (boundary, x) in bytes {
+            // Detect overflows into the first slot.
+            if self.fold_after.is_none() && pushed == 8 {
+                break;
+            }
+
+            let repeated = !boundary && x | 32 == prev;
+            prev = x | 32;
+            if !repeated {
+                run = None;
+            }
+
+            let phone = if boundary {
+                tables.phone(x)
+            } else if self.soft {
+                tables.filter_soft(last, x)
+            } else {
+                tables.filter(last, x)
+            };
+
+            // The new value of the last phone, if it is modified rather than pushed.
+            let modified = match phone {
+                Some(phone) if boundary || phone & 1 != last & 1 => {
+                    match self.fold_after {
+                        Some(slots) if pushed >= slots as usize => {
+                            // Fold into the slot of the phone `slots` phones earlier.
+                            shift = 8 * (slots as usize - 1 - pushed % slots as usize);
+                            res ^= (phone as u64) << shift;
+                        }
+                        _ => {
+                            res = res << 8 | phone as u64;
+                            shift = 0;
+                        }
+                    }
+                    last = phone;
+                    pushed += 1;
+                    // Only a pushed vowel can be marked as long.
+                    run = if raw::is_vowel(x) { Some(0u8) } else { None };
+                    None
+                }
+                // A nudged phone replaces the previous one.
+                Some(phone) => Some(phone),
+                None if (self.vowel_length || self.vowel_runs) && repeated && run.is_some() => {
+                    let repetitions = run.map_or(0, |x| x.saturating_add(1));
+                    run = Some(repetitions);
+                    match repetitions {
+                        1 => Some(last | raw::VOWEL_LENGTH),
+                        2 if self.vowel_runs => Some(last | raw::VOWEL_OVERLONG),
+                        _ => None,
+                    }
+                }
+                None => None,
+            };
+
+            if let Some(phone) = modified {
+                res ^= ((last ^ phone) as u64) << shift;
+                last = phone;
+            }
+        }
+
+        Hash {
+            hash: res | (first_byte << 56),
+        }
+    }
+}
+
+/// Deserialize `HashConfig::fold_after`, rejecting the numbers of phones its builder panics on.
+#[cfg(feature = "serde")]
+fn deserialize_fold_after<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u8>, D::Error> {
+    match Option::<u8>::deserialize(deserializer)? {
+        Some(n) if !(1..=8).contains(&n) => Err(de::Error::invalid_value(
+            de::Unexpected::Unsigned(n as u64),
+            &"a number of phones between 1 and 8",
+        )),
+        n => Ok(n),
+    }
+}
+
+/// A hasher with a fixed configuration.
+///
+/// This is created by [`HashConfig::into_hasher`](./struct.HashConfig.html#method.into_hasher),
+/// and gives the same hashes as [`HashConfig::build_hash`](./struct.HashConfig.html#method.build_hash),
+/// but compiles the phone tables into lookups of every byte up front.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::{HashConfig, Lang};
+///
+/// let config = HashConfig::new().lang(Lang::English).split_camel_case(true);
+/// let hasher = config.clone().into_hasher();
+/// assert_eq!(hasher.hash("knightRider"), config.build_hash("knightRider"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConfiguredHasher {
+    /// The configuration.
+    config: HashConfig,
+    /// The compiled phone tables of the configuration.
+    tables: raw::CompiledTables,
+}
+
+impl ConfiguredHasher {
+    /// Phonetically hash this string under the configuration.
+    pub fn hash(&self, string: &str) -> Hash {
+        self.config.build_preprocessed(&self.tables, &self.config.preprocess(string))
+    }
+
+    /// The configuration of the hasher.
+    #[inline]
+    pub fn config(&self) -> &HashConfig {
+        &self.config
+    }
+}
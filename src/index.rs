@@ -0,0 +1,605 @@
+//! Phonetic indexes of words.
+
+use std::collections::HashMap;
+#[cfg(feature = "mmap")]
+use std::fs::File;
+#[cfg(feature = "mmap")]
+use std::io::{self, Write};
+#[cfg(feature = "mmap")]
+use std::path::Path;
+#[cfg(feature = "mmap")]
+use std::str;
+
+#[cfg(feature = "mmap")]
+use memmap2::Mmap;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use {weights, Hash};
+
+/// An index of spellings by their phonetic hash.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::index::PhoneticIndex;
+///
+/// let mut index = PhoneticIndex::from_words(&["jumbo", "horse", "meyer"]);
+/// index.insert("maier");
+///
+/// # #[cfg(feature = "weights-graduated")]
+/// assert_eq!(index.lookup("jumpo", 15), ["jumbo"]);
+/// assert!(index.remove("horse"));
+/// assert!(index.lookup("horse", 0).is_empty());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PhoneticIndex {
+    /// The spellings of each hash.
+    ///
+    /// No bucket is empty.
+    buckets: HashMap<Hash, Vec<String>>,
+    /// The total number of spellings.
+    len: usize,
+}
+
+impl PhoneticIndex {
+    /// Create an empty index.
+    #[inline]
+    pub fn new() -> PhoneticIndex {
+        PhoneticIndex::default()
+    }
+
+    /// Build an index of some words.
+    pub fn from_words<I>(words: I) -> PhoneticIndex
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut index = PhoneticIndex::new();
+        for word in words {
+            index.insert(word.as_ref());
+        }
+
+        index
+    }
+
+    /// Build an index of some words in parallel.
+    ///
+    /// This gives the same index as [`from_words`](#method.from_words), but hashes the words in
+    /// parallel.
+    ///
+    /// This requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    pub fn par_build<S: AsRef<str> + Sync>(words: &[S]) -> PhoneticIndex {
+        let hashes: Vec<(Hash, &str)> = words.par_iter().map(|x| (Hash::new(x.as_ref()), x.as_ref())).collect();
+
+        let mut index = PhoneticIndex::new();
+        for (hash, word) in hashes {
+            let bucket = index.buckets.entry(hash).or_default();
+            if !bucket.iter().any(|x| x == word) {
+                bucket.push(word.to_owned());
+                index.len += 1;
+            }
+        }
+
+        index
+    }
+
+    /// Insert a spelling into the index.
+    ///
+    /// Inserting a spelling, which is already present, does nothing.
+    pub fn insert(&mut self, word: &str) {
+        let bucket = self.buckets.entry(Hash::new(word)).or_default();
+
+        if !bucket.iter().any(|x| x == word) {
+            bucket.push(word.to_owned());
+            self.len += 1;
+        }
+    }
+
+    /// Merge another index into this one.
+    ///
+    /// The spellings of every hash are unioned, so spellings present in both indexes are kept
+    /// once. This allows building partial indexes of shards of a corpus, and merging them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::index::PhoneticIndex;
+    ///
+    /// let mut index = PhoneticIndex::from_words(&["jumbo", "horse"]);
+    /// index.merge(PhoneticIndex::from_words(&["jumpo", "horse"]));
+    ///
+    /// assert_eq!(index.len(), 3);
+    /// # #[cfg(feature = "weights-graduated")]
+    /// assert_eq!(index.lookup("jumbo", 15), ["jumbo", "jumpo"]);
+    /// ```
+    pub fn merge(&mut self, other: PhoneticIndex) {
+        for (hash, words) in other.buckets {
+            let bucket = self.buckets.entry(hash).or_default();
+            for word in words {
+                if !bucket.contains(&word) {
+                    bucket.push(word);
+                    self.len += 1;
+                }
+            }
+        }
+    }
+
+    /// Remove a spelling from the index.
+    ///
+    /// This returns `true` if the spelling was present.
+    pub fn remove(&mut self, word: &str) -> bool {
+        let hash = Hash::new(word);
+
+        let removed = match self.buckets.get_mut(&hash) {
+            Some(bucket) => match bucket.iter().position(|x| x == word) {
+                Some(pos) => {
+                    bucket.swap_remove(pos);
+                    true
+                }
+                None => false,
+            },
+            None => false,
+        };
+
+        if removed {
+            self.len -= 1;
+            if self.buckets[&hash].is_empty() {
+                self.buckets.remove(&hash);
+            }
+        }
+
+        removed
+    }
+
+    /// Get the spellings, which have the same hash as some word.
+    pub fn get(&self, word: &str) -> &[String] {
+        self.buckets.get(&Hash::new(word)).map_or(&[], |bucket| &bucket[..])
+    }
+
+    /// Find the spellings within some graduated distance of a word.
+    ///
+    /// The spellings are ordered by distance, and then alphabetically.
+    pub fn lookup(&self, word: &str, max_dist: u32) -> Vec<&str> {
+        let hash = Hash::new(word);
+
+        let mut res = Vec::new();
+        for (&bucket_hash, bucket) in &self.buckets {
+            let dist = (hash - bucket_hash).dist();
+            if dist <= max_dist {
+                res.extend(bucket.iter().map(|x| (dist, &x[..])));
+            }
+        }
+
+        res.sort();
+        res.into_iter().map(|(_, x)| x).collect()
+    }
+
+    /// Find the spellings within some graduated distance of a word in parallel.
+    ///
+    /// This gives the same spellings as [`lookup`](#method.lookup), but scans the buckets in
+    /// parallel.
+    ///
+    /// This requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    pub fn par_lookup(&self, word: &str, max_dist: u32) -> Vec<&str> {
+        let hash = Hash::new(word);
+
+        let mut res: Vec<(u32, &str)> = self
+            .buckets
+            .par_iter()
+            .filter_map(|(&bucket_hash, bucket)| {
+                let dist = (hash - bucket_hash).dist();
+                if dist <= max_dist {
+                    Some(bucket.par_iter().map(move |x| (dist, &x[..])))
+                } else {
+                    None
+                }
+            })
+            .flatten()
+            .collect();
+
+        res.par_sort();
+        res.into_iter().map(|(_, x)| x).collect()
+    }
+
+    /// The number of spellings in the index.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Is the index empty?
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// A key of spellings by their approximate sound.
+///
+/// This is the [bucket](../struct.Hash.html#method.bucket) of the hash of a word, after dropping
+/// some number of its least significant bits (the coarseness), for grouping spellings in e.g. a
+/// `HashMap<PhoneticKey, Vec<String>>`. Two keys are equal if they have the same coarseness and
+/// the same bucket. With a coarseness of 0, the key is the hash itself, and with a coarseness of
+/// 56, the first letter.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::index::PhoneticKey;
+///
+/// assert_eq!(PhoneticKey::from_word("maier", 0), PhoneticKey::from_word("meyer", 0));
+/// assert_eq!(PhoneticKey::from_word("jumbo", 56), PhoneticKey::from_word("jumpo", 56));
+/// assert!(PhoneticKey::from_word("jumbo", 0) != PhoneticKey::from_word("jumpo", 0));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PhoneticKey {
+    /// The number of dropped bits.
+    coarseness: u32,
+    /// The remaining bits.
+    bucket: u64,
+}
+
+impl PhoneticKey {
+    /// The key of a hash.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `coarseness` is greater than 64.
+    #[inline]
+    pub fn from_hash(hash: Hash, coarseness: u32) -> PhoneticKey {
+        assert!(coarseness <= 64, "the coarseness must be at most 64");
+
+        PhoneticKey {
+            coarseness,
+            bucket: hash.bucket(64 - coarseness),
+        }
+    }
+
+    /// The key of a word.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `coarseness` is greater than 64.
+    #[inline]
+    pub fn from_word(word: &str, coarseness: u32) -> PhoneticKey {
+        PhoneticKey::from_hash(Hash::new(word), coarseness)
+    }
+}
+
+/// A deduplicator of near-duplicate spellings.
+///
+/// Every spelling seen is compared to the previously kept ones, which are bucketed by their first
+/// byte, such that only the buckets within reach of the threshold are scanned.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::index::Deduper;
+///
+/// let mut deduper = Deduper::new(15);
+/// assert_eq!(deduper.see("jumbo"), None);
+/// # #[cfg(feature = "weights-graduated")]
+/// assert_eq!(deduper.see("horse"), None);
+/// # #[cfg(feature = "weights-graduated")]
+/// assert_eq!(deduper.see("jumpo"), Some("jumbo"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Deduper {
+    /// The maximal graduated distance of near-duplicates.
+    threshold: u32,
+    /// The kept spellings.
+    words: Vec<(Hash, String)>,
+    /// The indices of the kept spellings by their first byte.
+    buckets: Vec<Vec<usize>>,
+}
+
+impl Deduper {
+    /// Create a deduplicator, which considers words within some graduated distance duplicates.
+    pub fn new(threshold: u32) -> Deduper {
+        Deduper {
+            threshold,
+            words: Vec::new(),
+            buckets: vec![Vec::new(); 256],
+        }
+    }
+
+    /// See a spelling.
+    ///
+    /// If it is a near-duplicate of a previously kept spelling, the closest such spelling is
+    /// returned (favoring the earliest seen in case of ties). Otherwise, the spelling is kept and
+    /// `None` is returned.
+    pub fn see(&mut self, word: &str) -> Option<&str> {
+        let hash = Hash::new(word);
+        let first = (u64::from(hash) >> 56) as u8;
+
+        let mut closest: Option<(u32, usize)> = None;
+        for bucket in 0..256usize {
+            if (bucket as u8 ^ first).count_ones() * weights::DEFAULT[7] > self.threshold {
+                continue;
+            }
+
+            for &i in &self.buckets[bucket] {
+                let dist = (hash - self.words[i].0).dist();
+                if dist <= self.threshold && closest.map_or(true, |x| (dist, i) < x) {
+                    closest = Some((dist, i));
+                }
+            }
+        }
+
+        match closest {
+            Some((_, i)) => Some(&self.words[i].1),
+            None => {
+                self.buckets[first as usize].push(self.words.len());
+                self.words.push((hash, word.to_owned()));
+                None
+            }
+        }
+    }
+
+    /// Get the kept spellings in the order they were seen.
+    pub fn words(&self) -> Vec<&str> {
+        self.words.iter().map(|x| &x.1[..]).collect()
+    }
+}
+
+/// A trie of spellings keyed on the bytes of their hash.
+///
+/// The path to a spelling is the bytes of its hash, from the first phone (the most significant
+/// byte) to the last. Since every byte adds to the graduated distance independently, lookups
+/// traverse the trie depth first, accumulating the distance of the path, and prune every subtree
+/// whose path is already further away than the bound. As the first bytes carry most of the weight,
+/// most subtrees are pruned near the root.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::index::LexiconTrie;
+///
+/// let mut trie = LexiconTrie::from_words(&["jumbo", "horse", "meyer"]);
+/// trie.insert("maier");
+///
+/// # #[cfg(feature = "weights-graduated")]
+/// assert_eq!(trie.lookup("jumpo", 15), ["jumbo"]);
+/// assert_eq!(trie.lookup("mayer", 0), ["maier", "meyer"]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct LexiconTrie {
+    /// The nodes, the root being the first.
+    nodes: Vec<TrieNode>,
+    /// The total number of spellings.
+    len: usize,
+}
+
+/// A node of a `LexiconTrie`.
+#[derive(Clone, Debug, Default)]
+struct TrieNode {
+    /// The byte and node index of the children, ordered by the byte.
+    children: Vec<(u8, usize)>,
+    /// The spellings, if the node is a leaf.
+    words: Vec<String>,
+}
+
+impl Default for LexiconTrie {
+    fn default() -> LexiconTrie {
+        LexiconTrie::new()
+    }
+}
+
+impl LexiconTrie {
+    /// Create an empty trie.
+    pub fn new() -> LexiconTrie {
+        LexiconTrie {
+            nodes: vec![TrieNode::default()],
+            len: 0,
+        }
+    }
+
+    /// Build a trie of some words.
+    pub fn from_words<I>(words: I) -> LexiconTrie
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut trie = LexiconTrie::new();
+        for word in words {
+            trie.insert(word.as_ref());
+        }
+
+        trie
+    }
+
+    /// Insert a spelling into the trie.
+    ///
+    /// Inserting a spelling, which is already present, does nothing.
+    pub fn insert(&mut self, word: &str) {
+        let hash = u64::from(Hash::new(word));
+
+        let mut node = 0;
+        for depth in 0..8 {
+            let byte = (hash >> (56 - 8 * depth)) as u8;
+            node = match self.nodes[node].children.binary_search_by_key(&byte, |&(x, _)| x) {
+                Ok(i) => self.nodes[node].children[i].1,
+                Err(i) => {
+                    let child = self.nodes.len();
+                    self.nodes.push(TrieNode::default());
+                    self.nodes[node].children.insert(i, (byte, child));
+                    child
+                }
+            };
+        }
+
+        let words = &mut self.nodes[node].words;
+        if !words.iter().any(|x| x == word) {
+            words.push(word.to_owned());
+            self.len += 1;
+        }
+    }
+
+    /// Find the spellings within some graduated distance of a word.
+    ///
+    /// The spellings are ordered by distance, and then alphabetically.
+    pub fn lookup(&self, word: &str, max_dist: u32) -> Vec<&str> {
+        let hash = u64::from(Hash::new(word));
+
+        let mut res = Vec::new();
+        // The node, its depth, and the distance of its path.
+        let mut stack = vec![(0, 0, 0)];
+        while let Some((node, depth, dist)) = stack.pop() {
+            let node = &self.nodes[node];
+            if depth == 8 {
+                res.extend(node.words.iter().map(|x| (dist, &x[..])));
+                continue;
+            }
+
+            let byte = (hash >> (56 - 8 * depth)) as u8;
+            let weight = weights::DEFAULT[7 - depth];
+            for &(child_byte, child) in &node.children {
+                let child_dist = dist + (byte ^ child_byte).count_ones() * weight;
+                if child_dist <= max_dist {
+                    stack.push((child, depth + 1, child_dist));
+                }
+            }
+        }
+
+        res.sort();
+        res.into_iter().map(|(_, x)| x).collect()
+    }
+
+    /// The number of spellings in the trie.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Is the trie empty?
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// A memory-mapped dictionary sorted by hash.
+///
+/// The dictionary file consists of lines of the hash (as 16 lowercase hexadecimal digits)
+/// followed by a space and the spelling, sorted by the hash. Such a file is created by
+/// [`MmapDict::write`](#method.write).
+///
+/// Rather than loading the file, lookups binary search to the regions of the words sharing a first
+/// byte within reach of the threshold and only scan those, leveraging that the first byte is the
+/// most significant byte of the hash.
+///
+/// This requires the `mmap` feature.
+#[cfg(feature = "mmap")]
+pub struct MmapDict {
+    map: Mmap,
+}
+
+#[cfg(feature = "mmap")]
+impl MmapDict {
+    /// Write a dictionary file of some words.
+    ///
+    /// Since every word takes a line, this fails with `io::ErrorKind::InvalidInput` (before
+    /// writing anything) if a word contains a newline.
+    pub fn write<W: Write>(mut writer: W, words: &[&str]) -> io::Result<()> {
+        if words.iter().any(|x| x.contains('\n')) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "a word contains a newline"));
+        }
+
+        let mut entries: Vec<(u64, &str)> = words.iter().map(|x| (Hash::new(x).into(), *x)).collect();
+        entries.sort();
+
+        for (hash, word) in entries {
+            writeln!(writer, "{:016x} {}", hash, word)?;
+        }
+
+        Ok(())
+    }
+
+    /// Open a dictionary file.
+    ///
+    /// The file must not be modified while the dictionary is open.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<MmapDict> {
+        let file = File::open(path)?;
+
+        Ok(MmapDict {
+            // The mapping is read-only, and the file is required not to change.
+            map: unsafe { Mmap::map(&file)? },
+        })
+    }
+
+    /// Parse the line starting at some offset.
+    ///
+    /// This gives the hash and spelling (if the line is well-formed) and the offset of the next
+    /// line.
+    fn line(&self, start: usize) -> (Option<(u64, &str)>, usize) {
+        let data = &self.map[start..];
+        let len = data.iter().position(|&x| x == b'\n').unwrap_or(data.len());
+        let line = &data[..len];
+
+        let entry = if line.len() >= 17 && line[16] == b' ' {
+            let hash = str::from_utf8(&line[..16]).ok().and_then(|x| u64::from_str_radix(x, 16).ok());
+            let word = str::from_utf8(&line[17..]).ok();
+            hash.zip(word)
+        } else {
+            None
+        };
+
+        (entry, start + len + 1)
+    }
+
+    /// Find the offset of the first line, whose hash is at least some key.
+    fn lower_bound(&self, key: u64) -> usize {
+        let (mut lo, mut hi) = (0, self.map.len());
+
+        // `lo` is always the start of a line.
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let start = self.map[lo..mid].iter().rposition(|&x| x == b'\n').map_or(lo, |x| lo + x + 1);
+
+            match self.line(start) {
+                (Some((hash, _)), _) if hash >= key => hi = start,
+                (_, next) => lo = next,
+            }
+        }
+
+        lo
+    }
+
+    /// Find the spellings within some graduated distance of a word.
+    ///
+    /// The spellings are ordered by distance, and then alphabetically.
+    pub fn lookup(&self, word: &str, max_dist: u32) -> Vec<&str> {
+        let hash = Hash::new(word);
+        let first = (u64::from(hash) >> 56) as u8;
+
+        let mut res = Vec::new();
+        for bucket in 0..256u64 {
+            if (bucket as u8 ^ first).count_ones() * weights::DEFAULT[7] > max_dist {
+                continue;
+            }
+
+            let mut offset = self.lower_bound(bucket << 56);
+            while offset < self.map.len() {
+                let (entry, next) = self.line(offset);
+                offset = next;
+
+                if let Some((x, spelling)) = entry {
+                    if x >> 56 != bucket {
+                        break;
+                    }
+
+                    let dist = (hash - Hash { hash: x }).dist();
+                    if dist <= max_dist {
+                        res.push((dist, spelling));
+                    }
+                }
+            }
+        }
+
+        res.sort();
+        res.into_iter().map(|(_, x)| x).collect()
+    }
+}
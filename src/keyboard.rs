@@ -0,0 +1,120 @@
+//! Keyboard typos.
+//!
+//! Some misspellings are slips of the fingers rather than of the ear (e.g. "jello" for "hello"),
+//! which the phonetic distance does not capture. This module requires the `keyboard` feature.
+
+use Hash;
+
+/// The rows of letters of a QWERTY keyboard.
+const ROWS: [&[u8]; 3] = [b"qwertyuiop", b"asdfghjkl", b"zxcvbnm"];
+
+/// The cost of substituting a letter by one of an adjacent key.
+const ADJACENT_COST: f64 = 0.5;
+
+/// The position of the key of a letter on a QWERTY keyboard.
+///
+/// This is the row, and the doubled horizontal position, where every row is shifted half a key
+/// to the right of the row above it.
+fn position(x: char) -> Option<(i32, i32)> {
+    let x = x.to_ascii_lowercase();
+
+    ROWS.iter().enumerate().find_map(|(row, keys)| {
+        let col = keys.iter().position(|&key| key as char == x)?;
+        Some((row as i32, 2 * col as i32 + row as i32))
+    })
+}
+
+/// Are the keys of two letters adjacent on a QWERTY keyboard?
+///
+/// The keys are adjacent if they neighbor each other in a row, or touch in the row above or
+/// below. Case is ignored, and characters other than ASCII letters are adjacent to nothing.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::keyboard::is_adjacent;
+///
+/// assert!(is_adjacent('h', 'j'));
+/// assert!(is_adjacent('s', 'W'));
+/// assert!(!is_adjacent('h', 'l'));
+/// ```
+pub fn is_adjacent(a: char, b: char) -> bool {
+    match (position(a), position(b)) {
+        (Some((row_a, x_a)), Some((row_b, x_b))) => match (row_a - row_b).abs() {
+            0 => (x_a - x_b).abs() == 2,
+            1 => (x_a - x_b).abs() == 1,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// The keyboard edit distance of two words.
+///
+/// This is the edit distance of the characters (ignoring case), where inserting, deleting, or
+/// substituting a character costs 1, except for substituting a letter by one of an adjacent key,
+/// which costs 0.5.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::keyboard::typo_distance;
+///
+/// assert_eq!(typo_distance("hello", "jello"), 0.5);
+/// assert_eq!(typo_distance("hello", "cello"), 1.0);
+/// ```
+pub fn typo_distance(a: &str, b: &str) -> f64 {
+    let b: Vec<char> = b.chars().map(|x| x.to_ascii_lowercase()).collect();
+
+    // The costs of the previous row of the table.
+    let mut prev: Vec<f64> = (0..=b.len()).map(|j| j as f64).collect();
+    for (i, x) in a.chars().map(|x| x.to_ascii_lowercase()).enumerate() {
+        let mut row = Vec::with_capacity(b.len() + 1);
+        row.push(i as f64 + 1.0);
+        for (j, &y) in b.iter().enumerate() {
+            let substitution = if x == y {
+                0.0
+            } else if is_adjacent(x, y) {
+                ADJACENT_COST
+            } else {
+                1.0
+            };
+
+            let cost = (prev[j] + substitution).min(prev[j + 1] + 1.0).min(row[j] + 1.0);
+            row.push(cost);
+        }
+        prev = row;
+    }
+
+    prev[b.len()]
+}
+
+/// A blend of the phonetic and the keyboard distance of two words.
+///
+/// This is `phon_weight` times the phonetic distance, i.e. one minus the
+/// [normalized](../struct.Difference.html#method.normalized) similarity, plus `1 - phon_weight`
+/// times the [keyboard distance](./fn.typo_distance.html) divided by the length of the longer
+/// word. Hence, `phon_weight` must be between 0 (only the keyboard) and 1 (only the phones), and
+/// the distance is between 0 and 1 too.
+///
+/// # Panics
+///
+/// This panics if `phon_weight` is not between 0 and 1.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::Hash;
+/// use eudex::keyboard::combined_typo_distance;
+///
+/// let phonetic = 1.0 - (Hash::new("gate") - Hash::new("fate")).normalized();
+/// assert!(combined_typo_distance("gate", "fate", 0.5) < phonetic);
+/// ```
+pub fn combined_typo_distance(a: &str, b: &str, phon_weight: f64) -> f64 {
+    assert!((0.0..=1.0).contains(&phon_weight), "the weight must be between 0 and 1");
+
+    let len = a.chars().count().max(b.chars().count());
+    let typo = if len == 0 { 0.0 } else { typo_distance(a, b) / len as f64 };
+
+    phon_weight * (1.0 - (Hash::new(a) - Hash::new(b)).normalized()) + (1.0 - phon_weight) * typo
+}
@@ -0,0 +1,244 @@
+//! Language specific preprocessing.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A language mode.
+///
+/// The generic tables are a compromise between many languages. A language mode rewrites the
+/// spelling of a word before hashing, such that the language's own spelling conventions (e.g.
+/// digraphs for single sounds) are hashed like their phones.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Lang {
+    /// Hungarian and Polish (as well as the Czech and Slovak diacritics).
+    ///
+    /// The diacritics of the Latin script are stripped, and the following digraphs are mapped to
+    /// single letters:
+    ///
+    /// | Digraphs   | Letter | Sound         |
+    /// |------------|--------|---------------|
+    /// | cs, cz     | c      | \[t͡ʃ\]       |
+    /// | ch         | h      | \[x\]         |
+    /// | sz, sh     | s      | \[s\] / \[ʃ\] |
+    /// | zs, zh, rz | z      | \[ʒ\]         |
+    ///
+    /// Moreover, w is mapped to v, as in Polish. This makes e.g. "Szabó" and "Sabo", or "Chmiel"
+    /// and "Hmiel" collide. As an exception, the ch of the common romanization "Kovach" of
+    /// "Kovács" is mapped to c.
+    CentralEuropean,
+    /// English.
+    ///
+    /// The word is lowercased, and the silent letter of the following initial clusters is
+    /// stripped:
+    ///
+    /// | Cluster | Letter | Example         |
+    /// |---------|--------|-----------------|
+    /// | kn      | n      | knight, night   |
+    /// | gn      | n      | gnat, nat       |
+    /// | pn      | n      | pneumonia       |
+    /// | mn      | n      | mnemonic        |
+    /// | ps      | s      | psych, sych     |
+    /// | pt      | t      | pterodactyl     |
+    /// | wr      | r      | write, rite     |
+    ///
+    /// Since the first letter weighs more than all the other phones, this makes e.g. "knight" and
+    /// "night" collide.
+    English,
+    /// German.
+    ///
+    /// The word is lowercased, and the letters, which have a conventional ASCII spelling, are
+    /// spelled out:
+    ///
+    /// | Letter | Spelling |
+    /// |--------|----------|
+    /// | ß      | ss       |
+    /// | ä      | ae       |
+    /// | ö      | oe       |
+    /// | ü      | ue       |
+    ///
+    /// This makes e.g. "Fuße" and "Fusse", or "Müller" and "Mueller" collide.
+    German,
+    /// Romanized Arabic and Hebrew.
+    ///
+    /// These scripts are usually written without short vowels, so the vowels of their
+    /// romanizations vary wildly, whereas the consonantal root is stable. The word is reduced to
+    /// its consonant skeleton: the diacritics are stripped, the word is lowercased, and
+    ///
+    /// - characters other than letters, including modifier letters (e.g. the ' or ʾ of a hamza,
+    ///   or the ʿ of an ayin), are dropped,
+    /// - an initial vowel is mapped to a, as it is typically the alif or aleph,
+    /// - all other vowels (a, e, i, o, and u) are dropped,
+    /// - doubled consonants (e.g. the mm of a shadda) are collapsed.
+    ///
+    /// This makes e.g. "Muhammad", "Mohammed", and "Muhamed", or "Umar" and "Omar" collide.
+    Semitic,
+}
+
+impl Lang {
+    /// Rewrite a word according to the language's conventions.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Lang;
+    ///
+    /// assert_eq!(Lang::CentralEuropean.preprocess("Szabó"), "sabo");
+    /// ```
+    pub fn preprocess(self, string: &str) -> String {
+        match self {
+            Lang::CentralEuropean => central_european(string),
+            Lang::English => english(string),
+            Lang::German => german(string),
+            Lang::Semitic => semitic(string),
+        }
+    }
+}
+
+/// Romanized Hungarian names, whose ch stands for cs.
+const CH_ROMANIZATIONS: &[&str] = &["kovach"];
+
+/// Rewrite Hungarian and Polish spellings.
+fn central_european(string: &str) -> String {
+    let chars: Vec<char> = string.chars().map(|x| fold_diacritic(x).to_ascii_lowercase()).collect();
+    let romanized = CH_ROMANIZATIONS.iter().any(|x| x.chars().eq(chars.iter().cloned()));
+
+    let mut res = String::with_capacity(string.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let next = chars.get(i + 1).cloned();
+
+        let (letter, len) = match (chars[i], next) {
+            ('c', Some('s')) | ('c', Some('z')) => ('c', 2),
+            ('c', Some('h')) if romanized => ('c', 2),
+            ('c', Some('h')) => ('h', 2),
+            ('s', Some('z')) | ('s', Some('h')) => ('s', 2),
+            ('z', Some('s')) | ('z', Some('h')) | ('r', Some('z')) => ('z', 2),
+            ('w', _) => ('v', 1),
+            (x, _) => (x, 1),
+        };
+
+        res.push(letter);
+        i += len;
+    }
+
+    res
+}
+
+/// Strip the silent letters of English initial clusters.
+fn english(string: &str) -> String {
+    let string = string.to_lowercase();
+
+    let silent = matches!(
+        string.as_bytes(),
+        [b'k' | b'g' | b'p' | b'm', b'n', ..] | [b'p', b's' | b't', ..] | [b'w', b'r', ..]
+    );
+
+    if silent {
+        string[1..].to_owned()
+    } else {
+        string
+    }
+}
+
+/// Spell out the German letters outside of ASCII.
+fn german(string: &str) -> String {
+    let mut res = String::with_capacity(string.len());
+    for x in string.chars().flat_map(char::to_lowercase) {
+        match x {
+            'ß' => res.push_str("ss"),
+            'ä' => res.push_str("ae"),
+            'ö' => res.push_str("oe"),
+            'ü' => res.push_str("ue"),
+            x => res.push(x),
+        }
+    }
+
+    res
+}
+
+/// Reduce a romanized Arabic or Hebrew word to its consonant skeleton.
+fn semitic(string: &str) -> String {
+    let letters = strip_diacritics(string)
+        .chars()
+        // Spacing modifier letters (U+02B0 to U+02FF) transcribe the hamza and the ayin.
+        .filter(|x| x.is_alphabetic() && !('\u{2B0}'..='\u{2FF}').contains(x))
+        .flat_map(char::to_lowercase)
+        .collect::<String>();
+
+    let mut res = String::with_capacity(letters.len());
+    let mut prev = None;
+    for (i, x) in letters.chars().enumerate() {
+        let vowel = matches!(x, 'a' | 'e' | 'i' | 'o' | 'u');
+        if i == 0 && vowel {
+            res.push('a');
+        } else if !vowel && prev != Some(x) {
+            // Only a repeat of the previous letter is doubled, so "baba" keeps both b's.
+            res.push(x);
+        }
+        prev = Some(x);
+    }
+
+    res
+}
+
+/// Strip the diacritics of a string.
+///
+/// Precomposed letters are folded by [`fold_diacritic`](./fn.fold_diacritic.html), and combining
+/// diacritical marks (U+0300 to U+036F) of decomposed letters are dropped, such that both the
+/// composed and the decomposed (NFD) forms are stripped.
+pub(crate) fn strip_diacritics(string: &str) -> String {
+    string
+        .chars()
+        .filter(|x| !('\u{300}'..='\u{36F}').contains(x))
+        .map(fold_diacritic)
+        .collect()
+}
+
+/// Strip the diacritics of a Latin letter.
+///
+/// This covers the Latin-1 Supplement and Latin Extended-A blocks. Other characters, including
+/// ligatures and letters without an ASCII base (e.g. ß, æ, and þ), are returned unchanged.
+pub(crate) fn fold_diacritic(x: char) -> char {
+    match x {
+        'À'..='Å' | 'Ā' | 'Ă' | 'Ą' => 'A',
+        'à'..='å' | 'ā' | 'ă' | 'ą' => 'a',
+        'Ç' | 'Ć' | 'Ĉ' | 'Ċ' | 'Č' => 'C',
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => 'c',
+        'Ď' | 'Đ' => 'D',
+        'ď' | 'đ' => 'd',
+        'È'..='Ë' | 'Ē' | 'Ĕ' | 'Ė' | 'Ę' | 'Ě' => 'E',
+        'è'..='ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => 'e',
+        'Ĝ' | 'Ğ' | 'Ġ' | 'Ģ' => 'G',
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => 'g',
+        'Ĥ' | 'Ħ' => 'H',
+        'ĥ' | 'ħ' => 'h',
+        'Ì'..='Ï' | 'Ĩ' | 'Ī' | 'Ĭ' | 'Į' | 'İ' => 'I',
+        'ì'..='ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => 'i',
+        'Ĵ' => 'J',
+        'ĵ' => 'j',
+        'Ķ' => 'K',
+        'ķ' | 'ĸ' => 'k',
+        'Ĺ' | 'Ļ' | 'Ľ' | 'Ŀ' | 'Ł' => 'L',
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => 'l',
+        'Ñ' | 'Ń' | 'Ņ' | 'Ň' => 'N',
+        'ñ' | 'ń' | 'ņ' | 'ň' | 'ŉ' => 'n',
+        'Ò'..='Ö' | 'Ø' | 'Ō' | 'Ŏ' | 'Ő' => 'O',
+        'ò'..='ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => 'o',
+        'Ŕ' | 'Ŗ' | 'Ř' => 'R',
+        'ŕ' | 'ŗ' | 'ř' => 'r',
+        'Ś' | 'Ŝ' | 'Ş' | 'Š' => 'S',
+        'ś' | 'ŝ' | 'ş' | 'š' => 's',
+        'Ţ' | 'Ť' | 'Ŧ' => 'T',
+        'ţ' | 'ť' | 'ŧ' => 't',
+        'Ù'..='Ü' | 'Ũ' | 'Ū' | 'Ŭ' | 'Ů' | 'Ű' | 'Ų' => 'U',
+        'ù'..='ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => 'u',
+        'Ŵ' => 'W',
+        'ŵ' => 'w',
+        'Ý' | 'Ŷ' | 'Ÿ' => 'Y',
+        'ý' | 'ÿ' | 'ŷ' => 'y',
+        'Ź' | 'Ż' | 'Ž' => 'Z',
+        'ź' | 'ż' | 'ž' => 'z',
+        x => x,
+    }
+}
@@ -3,71 +3,822 @@
 //! Eudex is a Soundex-esque phonetic reduction/hashing algorithm, providing locality sensitive
 //! "hashes" of words, based on the spelling and pronunciation.
 
-#![cfg_attr(test, feature(test))]
-#[cfg(test)]
+#![cfg_attr(all(test, feature = "nightly"), feature(test))]
+#[cfg(all(test, feature = "nightly"))]
 extern crate test;
+#[cfg(feature = "lru")]
+extern crate lru;
+#[cfg(feature = "mmap")]
+extern crate memmap2;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
 
 use std::ops;
 
-pub mod raw;
-#[cfg(test)]
-mod tests;
+pub use builder::{HashBuilder, HashWriter};
+#[cfg(feature = "lru")]
+pub use cache::CachingHasher;
+pub use config::{ConfiguredHasher, HashConfig, UnmappedChar};
+pub use lang::Lang;
+pub use weights::WeightProfile;
+
+pub mod index;
+#[cfg(feature = "keyboard")]
+pub mod keyboard;
+pub mod phrase;
+pub mod raw;
+pub mod reference;
+pub mod search;
+pub mod stats;
+mod builder;
+#[cfg(feature = "lru")]
+mod cache;
+mod config;
+mod lang;
+mod weights;
+#[cfg(test)]
+mod tests;
+
+/// A phonetic hash.
+///
+/// Using the `Sub` implementation of the hashes will give you the difference.
+#[derive(Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Hash {
+    hash: u64,
+}
+
+impl Hash {
+    /// Phonetically hash this string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// println!("{:?}", Hash::new("lulz"));
+    /// ```
+    #[inline]
+    pub fn new(string: &str) -> Hash {
+        let string = string.as_bytes();
+
+        let mut b = 0;
+        let first_byte = raw::map_first(*string.first().unwrap_or(&0)) as u64;
+
+        let mut res = 0;
+        let mut n = 1u8;
+
+        loop {
+            b += 1;
+            // Detect overflows into the first slot.
+            if n == 0 || b >= string.len() {
+                break;
+            }
+
+            if let Some(x) = raw::filter(res as u8, string[b]) {
+                res <<= 8;
+                res |= x as u64;
+                // Bit shifting is slightly faster than addition on certain (especially older)
+                // microprocessors.  Is this premature optimization? Yes, yes it is.
+                n <<= 1;
+            }
+        }
+
+        Hash {
+            hash: res | (first_byte << 56),
+        }
+    }
+
+    /// Phonetically hash this string, and count its phones.
+    ///
+    /// The count is the number of phones kept by the hash, including the first letter, but
+    /// continues past the phones, which fit into the hash (saturating at 255). This is the length
+    /// to pass to [`Difference::dist_with_length`](./struct.Difference.html#method.dist_with_length).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// assert_eq!(Hash::new_with_len("comp"), (Hash::new("comp"), 2));
+    /// assert_eq!(Hash::new_with_len("computer").1, 4);
+    /// assert_eq!(Hash::new_with_len("").1, 0);
+    /// ```
+    pub fn new_with_len(string: &str) -> (Hash, u8) {
+        let bytes = string.as_bytes();
+
+        let mut len = if bytes.is_empty() { 0 } else { 1u8 };
+        let mut last = 0;
+        for &x in bytes.iter().skip(1) {
+            if let Some(x) = raw::filter(last, x) {
+                last = x;
+                len = len.saturating_add(1);
+            }
+        }
+
+        (Hash::new(string), len)
+    }
+
+    /// Phonetically hash an IPA transcription.
+    ///
+    /// Rather than the spelling, this hashes the phones of the transcription, which are mapped
+    /// directly to the phone tables (see [`raw::ipa_phone`](./raw/fn.ipa_phone.html)), giving
+    /// hashes comparable to the ones of the spelling. Affricates can be written with or without
+    /// tie bar (e.g. "t͡ʃ" or "tʃ") or as a ligature ("ʧ"). Delimiters, stress and length marks, and
+    /// diacritics are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// assert!((Hash::from_ipa("/ˈdʒʌmboʊ/") - Hash::new("jumbo")).similar());
+    /// ```
+    pub fn from_ipa(string: &str) -> Hash {
+        let mut symbols = Vec::with_capacity(string.len());
+        for x in string.chars() {
+            match (symbols.last(), x) {
+                (Some(&'t'), 'ʃ') => *symbols.last_mut().unwrap() = 'ʧ',
+                (Some(&'d'), 'ʒ') => *symbols.last_mut().unwrap() = 'ʤ',
+                (_, x) if raw::ipa_phone(x).is_some() => symbols.push(x),
+                _ => (),
+            }
+        }
+
+        let first_byte = raw::ipa_map_first(*symbols.first().unwrap_or(&'\0')) as u64;
+
+        let mut res = 0;
+        let mut n = 1u8;
+        for &x in symbols.iter().skip(1) {
+            // Detect overflows into the first slot.
+            if n == 0 {
+                break;
+            }
+
+            if let Some(x) = raw::ipa_phone(x).filter(|&x| x & 1 != res as u8 & 1) {
+                res <<= 8;
+                res |= x as u64;
+                n <<= 1;
+            }
+        }
+
+        Hash {
+            hash: res | (first_byte << 56),
+        }
+    }
+
+    /// Get the consensus hash of several spellings of a word.
+    ///
+    /// Every spelling is hashed, and every bit of the consensus is set if it is set in more than
+    /// half of the hashes. Ties, including the case of no spellings, favor zero. As such, the
+    /// consensus is robust to a minority of noisy spellings.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// assert_eq!(Hash::consensus(&["maier", "meyer", "horse"]), Hash::new("meyer"));
+    /// ```
+    pub fn consensus(words: &[&str]) -> Hash {
+        Hash::centroid(&words.iter().map(|word| Hash::new(word)).collect::<Vec<_>>())
+    }
+
+    /// Get the centroid of a cluster of hashes.
+    ///
+    /// This is the per-bit majority of the hashes, as in [`consensus`](#method.consensus), but of
+    /// arbitrary hashes: every bit of the centroid is set if it is set in more than half of the
+    /// hashes, and ties favor zero. The centroid minimizes the summed Hamming distance to the
+    /// hashes, making it a representative of e.g. a cluster of an index.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// let cluster = [Hash::new("maier"), Hash::new("meyer"), Hash::new("mayer")];
+    /// assert_eq!(Hash::centroid(&cluster), Hash::new("meyer"));
+    /// ```
+    pub fn centroid(hashes: &[Hash]) -> Hash {
+        let mut counts = [0usize; 64];
+        for hash in hashes {
+            for (i, count) in counts.iter_mut().enumerate() {
+                *count += (hash.hash >> i & 1) as usize;
+            }
+        }
+
+        let mut res = 0;
+        for (i, &count) in counts.iter().enumerate() {
+            if 2 * count > hashes.len() {
+                res |= 1 << i;
+            }
+        }
+
+        Hash { hash: res }
+    }
+
+    /// Phonetically hash this string under a configuration, rejecting unmapped characters if
+    /// strict.
+    ///
+    /// This is equivalent to
+    /// [`HashConfig::try_build_hash`](./struct.HashConfig.html#method.try_build_hash).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::{Hash, HashConfig};
+    ///
+    /// let strict = HashConfig::new().strict(true);
+    /// assert_eq!(Hash::try_new("jumbo", &strict), Ok(Hash::new("jumbo")));
+    /// assert!(Hash::try_new("日本", &strict).is_err());
+    /// assert!(Hash::try_new("日本", &HashConfig::new()).is_ok());
+    /// ```
+    #[inline]
+    pub fn try_new(string: &str, config: &HashConfig) -> Result<Hash, UnmappedChar> {
+        config.try_build_hash(string)
+    }
+
+    /// Phonetically hash every window of some number of characters of this string.
+    ///
+    /// This gives the hash of every sliding window of `n` characters, from the first to the last,
+    /// such that phonetic substrings (e.g. "phon" in "telephone") can be indexed for partial
+    /// matches. A string shorter than `n` characters gives the hash of the whole string.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `n` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// assert!(Hash::ngrams("telephone", 4).any(|x| x == Hash::new("phon")));
+    /// assert_eq!(Hash::ngrams("tele", 8).collect::<Vec<_>>(), [Hash::new("tele")]);
+    /// ```
+    pub fn ngrams<'a>(string: &'a str, n: usize) -> impl Iterator<Item = Hash> + 'a {
+        assert!(n > 0, "the windows must be nonempty");
+
+        // The character boundaries.
+        let bounds: Vec<usize> = string.char_indices().map(|(i, _)| i).chain(Some(string.len())).collect();
+        // A short string has a single window.
+        let n = n.min(bounds.len() - 1);
+
+        (0..bounds.len() - n).map(move |i| Hash::new(&string[bounds[i]..bounds[i + n]]))
+    }
+
+    /// Phonetically hash the reverse of this string.
+    ///
+    /// As the first letters weigh the most, the hash of the reversed string is dominated by the
+    /// ending of the word, so words, which rhyme, are close.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// # #[cfg(feature = "weights-graduated")]
+    /// assert!((Hash::rhyme_key("station") - Hash::rhyme_key("nation")).similar());
+    /// assert_eq!(Hash::rhyme_key("lol"), Hash::new("lol"));
+    /// ```
+    pub fn rhyme_key(string: &str) -> Hash {
+        Hash::new(&string.chars().rev().collect::<String>())
+    }
+
+    /// Phonetically hash this string forward and in reverse.
+    ///
+    /// This gives `(Hash::new(string), Hash::rhyme_key(string))`, to be compared with
+    /// [`Difference::symmetric_dist`](./struct.Difference.html#method.symmetric_dist).
+    #[inline]
+    pub fn symmetric(string: &str) -> (Hash, Hash) {
+        (Hash::new(string), Hash::rhyme_key(string))
+    }
+
+    /// The hash representing the absence of a word.
+    ///
+    /// The empty string hashes to zero, like the words consisting of letters with zero phones
+    /// (e.g. "a"). This is a distinct sentinel, `u64::MAX`, which `Hash::new` never produces, as
+    /// its last byte is not a phone, allowing "no input" to be told apart from any input.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// assert!(Hash::empty().is_empty());
+    /// assert!(!Hash::new("").is_empty());
+    /// assert!(Hash::new("") != Hash::empty());
+    /// ```
+    #[inline]
+    pub fn empty() -> Hash {
+        Hash { hash: u64::MAX }
+    }
+
+    /// Is this the hash representing the absence of a word?
+    ///
+    /// See [`empty`](#method.empty).
+    #[inline]
+    pub fn is_empty(self) -> bool {
+        self == Hash::empty()
+    }
+
+    /// Does the hash carry essentially no signal?
+    ///
+    /// Tiny and vowel-only inputs (e.g. "a", "I", or "oo") are hashed to their first letter,
+    /// followed by nothing or bare vowels, so they collide with many unrelated words. The hash is
+    /// degenerate if it has fewer than two significant bytes, i.e. no trailing byte holds a
+    /// consonant (a phone other than 0 and 1) and only the first letter is left. Such inputs may
+    /// deserve exact matching instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// assert!(Hash::new("oo").is_degenerate());
+    /// assert!(!Hash::new("cat").is_degenerate());
+    /// ```
+    #[inline]
+    pub fn is_degenerate(self) -> bool {
+        self.unpack()[1..].iter().all(|&x| x >> 1 == 0)
+    }
+
+    /// Mix the bits of the hash, for bucketing.
+    ///
+    /// The bits of the hash are very unevenly used: the most significant byte is the first
+    /// letter, and the other bytes are sparse phones, so the low bits make poor bucket indices of
+    /// hash tables. This applies a reversible avalanche mix (the finalizer of SplitMix64), such
+    /// that distinct hashes still give distinct values, but every bit depends on every other.
+    ///
+    /// The mixed values are only keys, with no notion of phonetic distance, so they must not be
+    /// compared as hashes (e.g. with `dist`).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// assert_eq!(Hash::new("maier").mixed(), Hash::new("meyer").mixed());
+    /// assert!(Hash::new("jumbo").mixed() != Hash::new("jumpo").mixed());
+    /// ```
+    #[inline]
+    pub fn mixed(self) -> u64 {
+        let mut x = self.hash;
+        x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        x ^ (x >> 31)
+    }
+
+    /// Split the hash into its high and low 32-bit halves.
+    ///
+    /// This is for storage and interfaces without 64-bit integers. The first half holds the most
+    /// significant bits (i.e. the first phones), and the second half the least significant bits
+    /// (i.e. the last phones). See [`from_u32_pair`](#method.from_u32_pair) for the inverse.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// let (hi, lo) = Hash::new("jumbo").split_u32();
+    /// assert_eq!(Hash::from_u32_pair(hi, lo), Hash::new("jumbo"));
+    /// ```
+    #[inline]
+    pub fn split_u32(self) -> (u32, u32) {
+        ((self.hash >> 32) as u32, self.hash as u32)
+    }
+
+    /// The letters, which the word of this hash can start with.
+    ///
+    /// The first byte of the hash is the injective phone of the first letter, so it can be mapped
+    /// back through the tables of injective phones to the (lowercase) letters of ASCII and the
+    /// Latin-1 Supplement, whose phone it is. For an ASCII letter, this is usually the letter
+    /// alone. Note that the first byte of a word, which starts with a non-letter or a letter
+    /// outside of ASCII, is 0, which is the phone of w.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// assert_eq!(Hash::new("Jumbo").first_letter_candidates(), ['j']);
+    /// assert_eq!(Hash::new("wumbo").first_letter_candidates(), ['w']);
+    /// ```
+    pub fn first_letter_candidates(self) -> Vec<char> {
+        raw::first_letters((self.hash >> 56) as u8).collect()
+    }
+
+    /// The bytes packed into the hash.
+    ///
+    /// These are big-endian, so the first byte is the phone of the first letter (the byte at
+    /// `first_byte << 56`), followed by the trailing phones in order, such that the last phone is
+    /// the last byte. Short words are padded with zeros in front of their trailing phones.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// // j, followed by the phones of u, m, and p.
+    /// assert_eq!(Hash::new("jump").unpack(), [0b00000011, 0, 0, 0, 0, 1, 0b00000010, 0b01001001]);
+    /// ```
+    #[inline]
+    pub fn unpack(self) -> [u8; 8] {
+        self.hash.to_be_bytes()
+    }
+
+    /// Join a hash from its high and low 32-bit halves.
+    ///
+    /// This is the inverse of [`split_u32`](#method.split_u32).
+    #[inline]
+    pub fn from_u32_pair(hi: u32, lo: u32) -> Hash {
+        Hash {
+            hash: (hi as u64) << 32 | lo as u64,
+        }
+    }
+
+    /// Phonetically hash this string in some language mode.
+    ///
+    /// This hashes the string after [preprocessing](./enum.Lang.html#method.preprocess) it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::{Hash, Lang};
+    ///
+    /// assert_eq!(Hash::new_lang("Kovács", Lang::CentralEuropean), Hash::new_lang("Kovach", Lang::CentralEuropean));
+    /// ```
+    #[inline]
+    pub fn new_lang(string: &str, lang: Lang) -> Hash {
+        Hash::new(&lang.preprocess(string))
+    }
+
+    /// Phonetically hash this string with the diacritics stripped.
+    ///
+    /// The phone tables only map ASCII, so e.g. "Müller" loses its vowel. This strips the
+    /// diacritics of the Latin letters first, whether they are precomposed (as in "ü") or
+    /// decomposed (as in "u" followed by U+0308).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// assert!(Hash::new("Ñandu") != Hash::new("nandu"));
+    /// assert_eq!(Hash::new_normalized("Ñandu"), Hash::new("nandu"));
+    /// assert_eq!(Hash::new_normalized("N\u{303}andu"), Hash::new("nandu"));
+    /// ```
+    #[inline]
+    pub fn new_normalized(string: &str) -> Hash {
+        Hash::new(&lang::strip_diacritics(string))
+    }
+
+    /// Phonetically hash this string, normalizing common OCR confusions.
+    ///
+    /// Optical character recognition often confuses letters, which look alike, rather than sound
+    /// alike. Before hashing, the following confusions are normalized (case insensitively):
+    ///
+    /// | OCR output | Normalized |
+    /// |------------|------------|
+    /// | rn         | m          |
+    /// | cl         | d          |
+    /// | vv         | w          |
+    /// | 0          | o          |
+    /// | 1          | l          |
+    ///
+    /// Note that this also affects words legitimately containing these sequences (e.g. "clear"
+    /// is hashed as "dear").
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// assert_eq!(Hash::new_ocr("rnodern"), Hash::new_ocr("modern"));
+    /// assert_eq!(Hash::new_ocr("vvater"), Hash::new("water"));
+    /// ```
+    pub fn new_ocr(string: &str) -> Hash {
+        let string = string.as_bytes();
+
+        let mut builder = HashBuilder::new();
+        let mut i = 0;
+        while i < string.len() {
+            let x = string[i] | 32;
+            let next = string.get(i + 1).map(|&x| x | 32);
+
+            let (normalized, len) = match (x, next) {
+                (b'r', Some(b'n')) => (b'm', 2),
+                (b'c', Some(b'l')) => (b'd', 2),
+                (b'v', Some(b'v')) => (b'w', 2),
+                _ => match string[i] {
+                    b'0' => (b'o', 1),
+                    b'1' => (b'l', 1),
+                    x => (x, 1),
+                },
+            };
+
+            builder.push(normalized);
+            i += len;
+        }
+
+        builder.finish()
+    }
+
+    /// Phonetically hash this string, keeping every letter apart.
+    ///
+    /// This is the high-precision end of the spectrum: every letter is mapped to its own phone
+    /// (see [`raw::injective_phone`](./raw/fn.injective_phone.html)), vowels are not collapsed,
+    /// and only immediately repeated letters (e.g. "ll") are deduplicated. Case and characters
+    /// other than letters (e.g. punctuation) are still ignored. The first letter takes the most
+    /// significant byte, followed by at most seven trailing phones.
+    ///
+    /// This sacrifices recall for precision: spellings, which sound alike, but are spelled
+    /// differently, generally get far apart hashes, so this is meant for near-exact (e.g.
+    /// forensic) matching, rather than sound-alike matching.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// assert_eq!(Hash::new("maier"), Hash::new("meyer"));
+    /// assert_ne!(Hash::new_fine("maier"), Hash::new_fine("meyer"));
+    /// assert_eq!(Hash::new_fine("O'Neill"), Hash::new_fine("oneil"));
+    /// ```
+    pub fn new_fine(string: &str) -> Hash {
+        let mut phones = string.bytes().filter_map(raw::injective_phone);
+        let first = phones.next().unwrap_or(0);
+
+        let mut res = 0;
+        let mut prev = first;
+        let mut n = 0;
+        for x in phones {
+            if n == 7 {
+                break;
+            }
+
+            // Only skip exact repetitions.
+            if x != prev {
+                res = res << 8 | x as u64;
+                prev = x;
+                n += 1;
+            }
+        }
+
+        Hash {
+            hash: res | (first as u64) << 56,
+        }
+    }
+
+    /// Phonetically hash this string, with some phonetic properties masked out.
+    ///
+    /// The bits set in `mask` are zeroed in every trailing phone before it is packed, such that
+    /// differences in these properties vanish. The bits have the meanings of the phone table
+    /// (see [`raw::property_mask`](./raw/fn.property_mask.html)): nasal (2), fricative (4),
+    /// plosive (8), dental (16), liquid (32), labial (64), and confident (128). The discriminant
+    /// (1) is not a property, and is never masked. The first letter is kept as is.
+    ///
+    /// This is meant for ablation experiments, measuring the contribution of a property to the
+    /// matching.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// assert!(Hash::new("bin") != Hash::new("bim"));
+    /// // m and n differ only in being dental.
+    /// assert_eq!(Hash::new_masked("bin", 16), Hash::new_masked("bim", 16));
+    /// assert_eq!(Hash::new_masked("bin", 0), Hash::new("bin"));
+    /// ```
+    pub fn new_masked(string: &str, mask: u8) -> Hash {
+        let string = string.as_bytes();
+        let mask = !mask | 1;
+
+        let first_byte = raw::map_first(*string.first().unwrap_or(&0)) as u64;
+
+        let mut res = 0;
+        let mut n = 0;
+        for &x in string.iter().skip(1) {
+            // Detect overflows into the first slot.
+            if n == 8 {
+                break;
+            }
+
+            if let Some(x) = raw::filter(res as u8, x) {
+                res = res << 8 | (x & mask) as u64;
+                n += 1;
+            }
+        }
+
+        Hash {
+            hash: res | (first_byte << 56),
+        }
+    }
+
+    /// Fold the first phone, if it is a vowel, into a single canonical vowel.
+    ///
+    /// The first phone distinguishes all the properties of vowels, so words starting with
+    /// different vowels (e.g. "iesus" and "aesus") land in different buckets. Folding replaces a
+    /// vowel first phone by the bare vowel bit, such that all words starting with a vowel bucket
+    /// together, whereas consonant initials are left untouched. As the first trailing phone of a
+    /// word with eight trailing phones overflows into the first byte, only a first byte, which is
+    /// the phone of an ASCII vowel, is folded, so a long word may keep its vowel initial.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// assert_eq!(Hash::new("iesus").fold_initial_vowel(), Hash::new("aesus").fold_initial_vowel());
+    /// assert_eq!(Hash::new("jesus").fold_initial_vowel(), Hash::new("jesus"));
+    /// ```
+    #[inline]
+    pub fn fold_initial_vowel(self) -> Hash {
+        if self.vowel_initial() {
+            Hash {
+                hash: self.hash & !(0xFF << 56) | 1 << 63,
+            }
+        } else {
+            self
+        }
+    }
+
+    /// Does the word of this hash start with a vowel?
+    ///
+    /// The vowel bit of the first byte alone does not tell, as the first trailing phone of a word
+    /// with eight trailing phones is ORed into the first byte. Hence, this requires the first byte
+    /// to be the phone of an ASCII vowel (or the bare vowel bit of a folded initial). The phones of
+    /// the other vowels are not accepted, as overflowing consonant initials often take them (e.g.
+    /// "crnjolztuhlkbkm" has the first byte of æ).
+    fn vowel_initial(self) -> bool {
+        let first = (self.hash >> 56) as u8;
+        first == 0x80 || b"aeiouy".iter().any(|&x| raw::map_first(x) == first)
+    }
+
+    /// Get the bucket of the hash at some number of bits.
+    ///
+    /// This is the `bits` most significant bits of the hash, which are the first phones. Hence,
+    /// sharding by the bucket keeps words with the same first phones together.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `bits` is greater than 64.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// assert_eq!(Hash::new("jumbo").bucket(8), Hash::new("jumpo").bucket(8));
+    /// assert_eq!(Hash::new("jumbo").bucket(0), 0);
+    /// ```
+    #[inline]
+    pub fn bucket(self, bits: u32) -> u64 {
+        assert!(bits <= 64, "buckets have at most 64 bits");
+
+        self.hash.checked_shr(64 - bits).unwrap_or(0)
+    }
+
+    /// Do two hashes share the bucket at some number of bits?
+    ///
+    /// This is equivalent to comparing the buckets (see [`bucket`](#method.bucket)). In
+    /// particular, `same_bucket(.., 64)` is exact equality, and `same_bucket(.., 0)` is always
+    /// true.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `bits` is greater than 64.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// assert!(Hash::new("jumbo").same_bucket(Hash::new("jumpo"), 8));
+    /// assert!(!Hash::new("jumbo").same_bucket(Hash::new("jumpo"), 64));
+    /// assert!(Hash::new("maier").same_bucket(Hash::new("meyer"), 64));
+    /// ```
+    #[inline]
+    pub fn same_bucket(self, other: Hash, bits: u32) -> bool {
+        self.bucket(bits) == other.bucket(bits)
+    }
+
+    /// Split the hash into bands for locality-sensitive hashing.
+    ///
+    /// The 64 bits are split into `num_bands` contiguous bands, from the most significant bits to
+    /// the least significant bits, and every band is given as a key. When the bits do not divide
+    /// evenly, the first bands are one bit wider. Words, which share any band (at the same
+    /// position), can then be taken as the candidates matched by the full distance.
+    ///
+    /// More bands means narrower bands, which are more likely to be shared, giving more
+    /// candidates (higher recall, but lower precision). The first band holds the first phones, so
+    /// with few bands, only words with the same initials are candidates. The last bands hold the
+    /// last phones, which carry the least weight, and are shared the most.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `num_bands` is not between 2 and 64, since a band must fit a `u32`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// let a = Hash::new("jumbo").bands(8);
+    /// let b = Hash::new("jumpo").bands(8);
+    /// assert_eq!(a.len(), 8);
+    /// assert!(a.iter().zip(&b).any(|(x, y)| x == y));
+    /// ```
+    pub fn bands(self, num_bands: u32) -> Vec<u32> {
+        assert!((2..=64).contains(&num_bands), "the number of bands must be between 2 and 64");
+
+        let mut res = Vec::with_capacity(num_bands as usize);
+        let mut rest = self.hash;
+        let mut rest_bits = 64;
+        for i in 0..num_bands {
+            let bits = 64 / num_bands + (i < 64 % num_bands) as u32;
+            rest_bits -= bits;
+            res.push((rest >> rest_bits) as u32);
+            rest &= (1u64 << rest_bits).wrapping_sub(1);
+        }
 
-/// A phonetic hash.
-///
-/// Using the `Sub` implementation of the hashes will give you the difference.
-#[derive(Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Debug)]
-pub struct Hash {
-    hash: u64,
-}
+        res
+    }
 
-impl Hash {
-    /// Phonetically hash this string.
+    /// Get a coarse fingerprint, which is stable across versions.
+    ///
+    /// Improvements to the phone tables change the hashes, which breaks persisted hashes. The
+    /// fingerprint is derived from only the most robust properties, independently of the tables,
+    /// and will stay the same across patch releases. This makes it suitable as a bucket key to be
+    /// stored alongside the full hash.
+    ///
+    /// The five most significant bits hold the first letter (a is 0, z is 25, and 31 means that the
+    /// string does not start with an ASCII letter). The following bits hold the classes of up to
+    /// the next five consonants, two bits each: nasal (mn), plosive (bcdgkpqt), or fricative
+    /// (fhjsvxz). Repeated classes are counted once, and other characters are ignored. The least
+    /// significant bit is set if the word has more consonant classes than that.
     ///
     /// # Examples
     ///
     /// ```rust
     /// use eudex::Hash;
     ///
-    /// println!("{:?}", Hash::new("lulz"));
+    /// assert_eq!(Hash::stable_fingerprint("Smith"), Hash::stable_fingerprint("smyth"));
+    /// assert!(Hash::stable_fingerprint("smith") != Hash::stable_fingerprint("swift"));
     /// ```
-    #[inline]
-    pub fn new(string: &str) -> Hash {
+    pub fn stable_fingerprint(string: &str) -> u16 {
         let string = string.as_bytes();
 
-        let mut b = 0;
-        let first_byte = raw::map_first(*string.get(0).unwrap_or(&0)) as u64;
+        let first = match string.first() {
+            Some(&x) if (x | 32).is_ascii_lowercase() => ((x | 32) - b'a') as u16,
+            _ => 31,
+        };
 
-        let mut res = 0;
-        let mut n = 1u8;
+        let mut res = first << 11;
+        let mut prev = 0;
+        let mut n = 0;
+        for &x in string.iter().skip(1) {
+            let class = stable_class(x);
+            if class == 0 || class == prev {
+                continue;
+            }
 
-        loop {
-            b += 1;
-            // Detect overflows into the first slot.
-            if n == 0 || b >= string.len() {
+            if n == 5 {
+                res |= 1;
                 break;
             }
 
-            if let Some(x) = raw::filter(res as u8, string[b]) {
-                res <<= 8;
-                res |= x as u64;
-                // Bit shifting is slightly faster than addition on certain (especially older)
-                // microprocessors.  Is this premature optimization? Yes, yes it is.
-                n <<= 1;
-            }
+            res |= class << (9 - 2 * n);
+            prev = class;
+            n += 1;
         }
 
-        Hash {
-            hash: res | (first_byte << 56),
-        }
+        res
+    }
+}
+
+/// The stable class of a consonant.
+///
+/// This is 1 for nasals, 2 for plosives, 3 for fricatives, and 0 for everything else. It must not
+/// be changed, as it determines the stable fingerprints.
+fn stable_class(x: u8) -> u16 {
+    match x | 32 {
+        b'm' | b'n' => 1,
+        b'b' | b'c' | b'd' | b'g' | b'k' | b'p' | b'q' | b't' => 2,
+        b'f' | b'h' | b'j' | b's' | b'v' | b'x' | b'z' => 3,
+        _ => 0,
     }
 }
 
 /// Get the inner hash value.
-impl Into<u64> for Hash {
+impl From<Hash> for u64 {
     #[inline]
-    fn into(self) -> u64 {
-        self.hash
+    fn from(hash: Hash) -> u64 {
+        hash.hash
     }
 }
 
@@ -76,9 +827,11 @@ impl ops::Sub for Hash {
     type Output = Difference;
 
     #[inline]
+    #[allow(clippy::suspicious_arithmetic_impl)]
     fn sub(self, rhs: Hash) -> Difference {
         Difference {
             xor: self.hash ^ rhs.hash,
+            vowel_initials: self.vowel_initial() && rhs.vowel_initial(),
         }
     }
 }
@@ -87,6 +840,8 @@ impl ops::Sub for Hash {
 #[derive(Copy, Clone)]
 pub struct Difference {
     xor: u64,
+    /// Do both words start with a vowel?
+    vowel_initials: bool,
 }
 
 impl Difference {
@@ -95,6 +850,10 @@ impl Difference {
     /// This will assign different weights to each of the bytes Hamming weight and simply add it.
     /// For most use cases, this metric is the preferred one.
     ///
+    /// The weights are the graduated weights (see
+    /// [`WeightProfile::Graduated`](./enum.WeightProfile.html#variant.Graduated)), unless another
+    /// profile is selected crate-wide by the `weights-flat` or `weights-front-heavy` feature.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -104,14 +863,264 @@ impl Difference {
     /// ```
     #[inline]
     pub fn dist(self) -> u32 {
-        (self.xor as u8).count_ones() as u32
-            + ((self.xor >> 8 ) as u8).count_ones() as u32 * 2
-            + ((self.xor >> 16) as u8).count_ones() as u32 * 3
-            + ((self.xor >> 24) as u8).count_ones() as u32 * 5
-            + ((self.xor >> 32) as u8).count_ones() as u32 * 8
-            + ((self.xor >> 40) as u8).count_ones() as u32 * 13
-            + ((self.xor >> 48) as u8).count_ones() as u32 * 21
-            + ((self.xor >> 56) as u8).count_ones() as u32 * 34
+        weights::weighted(self.xor, &weights::DEFAULT)
+    }
+
+    /// The graduated distance normalized to a similarity.
+    ///
+    /// This maps the distance linearly to a similarity between 0 and 1, where 1 means that the
+    /// hashes are equal, and 0 means that every bit differs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// assert_eq!((Hash::new("maier") - Hash::new("meyer")).normalized(), 1.0);
+    /// assert!((Hash::new("jumbo") - Hash::new("jumpo")).normalized() > 0.9);
+    /// ```
+    #[inline]
+    pub fn normalized(self) -> f64 {
+        1.0 - self.dist() as f64 / weights::MAX_DIST as f64
+    }
+
+    /// The graduated weight of the agreeing bits.
+    ///
+    /// This is the positive dual of `dist`: the bits, which are equal in both hashes, are weighted
+    /// as in `dist`, such that a higher agreement means more similar words. As every bit either
+    /// agrees or differs, `agreement() + dist()` is exactly the weight of all the bits (696 under
+    /// the graduated weights), which is the agreement of equal hashes. Hence, thresholds on either
+    /// are equivalent, and the agreement is merely more intuitive in some settings.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// let diff = Hash::new("jumbo") - Hash::new("jumpo");
+    /// let equal = (Hash::new("maier") - Hash::new("meyer")).agreement();
+    /// assert_eq!(diff.agreement() + diff.dist(), equal);
+    /// # #[cfg(feature = "weights-graduated")]
+    /// assert_eq!(equal, 696);
+    /// ```
+    #[inline]
+    pub fn agreement(self) -> u32 {
+        weights::weighted(!self.xor, &weights::DEFAULT)
+    }
+
+    /// The distance under custom weights.
+    ///
+    /// This weighs the Hamming weight of every byte and adds them. The weights are given from the
+    /// last phone to the first phone.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// let diff = Hash::new("lulz") - Hash::new("lol");
+    /// # #[cfg(feature = "weights-graduated")]
+    /// assert_eq!(diff.weighted(&[1, 2, 3, 5, 8, 13, 21, 34]), diff.dist());
+    /// ```
+    #[inline]
+    pub fn weighted(self, weights: &[u32; 8]) -> u32 {
+        weights::weighted(self.xor, weights)
+    }
+
+    /// The distances under two sets of custom weights.
+    ///
+    /// This is equivalent to `(self.weighted(a), self.weighted(b))`, but counts the bits of every
+    /// byte once, which is useful for comparing weightings, e.g. when migrating thresholds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// let diff = Hash::new("lulz") - Hash::new("lol");
+    /// let flat = [1; 8];
+    /// # #[cfg(feature = "weights-graduated")]
+    /// assert_eq!(diff.dist_both(&[1, 2, 3, 5, 8, 13, 21, 34], &flat), (diff.dist(), diff.hamming()));
+    /// ```
+    pub fn dist_both(self, a: &[u32; 8], b: &[u32; 8]) -> (u32, u32) {
+        let mut res = (0, 0);
+        for i in 0..8 {
+            let bits = ((self.xor >> (8 * i)) as u8).count_ones();
+            res.0 += bits * a[i];
+            res.1 += bits * b[i];
+        }
+
+        res
+    }
+
+    /// The distance under a named weight profile.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::{Hash, WeightProfile};
+    ///
+    /// let diff = Hash::new("horse") - Hash::new("norse");
+    /// assert!(diff.dist_profile(WeightProfile::FrontHeavy) > diff.dist_profile(WeightProfile::Graduated));
+    /// ```
+    #[inline]
+    pub fn dist_profile(self, profile: WeightProfile) -> u32 {
+        self.weighted(&profile.weights())
+    }
+
+    /// The graduated distance ignoring the discriminants.
+    ///
+    /// The discriminant (the least significant bit of every byte) only serves to tag duplicates
+    /// when filtering, and to avoid collisions of the first phone (e.g. "b" and "p" differ only
+    /// in it). This masks out the discriminant of every byte before taking the graduated distance,
+    /// such that words only differing in the tagging are not penalized.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// assert_eq!((Hash::new("bannana") - Hash::new("panana")).dist_no_discriminant(), 0);
+    /// ```
+    #[inline]
+    pub fn dist_no_discriminant(self) -> u32 {
+        weights::weighted(self.xor & 0xFEFE_FEFE_FEFE_FEFE, &weights::DEFAULT)
+    }
+
+    /// The graduated distance with softened initial vowels.
+    ///
+    /// Words starting with different vowels are far apart (see
+    /// [`Hash::fold_initial_vowel`](./struct.Hash.html#method.fold_initial_vowel)). This metric lets
+    /// the first byte carry a weight of at most 2 when both words start with a vowel (or its weight
+    /// in `dist`, if lower), so it never exceeds `dist`. Consonant initials, as well as a vowel initial against a
+    /// consonant initial, are weighted as in `dist`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// let diff = Hash::new("iesus") - Hash::new("aesus");
+    /// # #[cfg(feature = "weights-graduated")]
+    /// assert!(diff.dist_soft_initial() < diff.dist());
+    /// assert!(diff.dist_soft_initial() <= diff.dist());
+    /// ```
+    #[inline]
+    pub fn dist_soft_initial(self) -> u32 {
+        if self.vowel_initials {
+            let first = ((self.xor >> 56) as u8).count_ones();
+            let weight = weights::DEFAULT[7];
+            self.dist() - first * weight + first * weight.min(2)
+        } else {
+            self.dist()
+        }
+    }
+
+    /// The graduated distance ignoring the vowels.
+    ///
+    /// Trailing vowels are the phones 0 (open) and 1 (close), which differ only in the
+    /// discriminant, so this masks out the discriminant of every trailing byte. The first byte
+    /// holds the properties of a vowel initial, so it is masked out entirely when both words
+    /// start with a vowel. Consonant initials are compared as in `dist`.
+    ///
+    /// Note that vowels also decide which of the following phones are deduplicated, so words
+    /// differing in a trailing vowel can still be apart, whereas words differing only in
+    /// the initial vowel get a distance of 0.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// let diff = Hash::new("ellen") - Hash::new("allen");
+    /// assert!(diff.dist() > 0);
+    /// assert_eq!(diff.dist_consonants_only(), 0);
+    /// ```
+    #[inline]
+    pub fn dist_consonants_only(self) -> u32 {
+        let first = if self.vowel_initials { 0 } else { 0xFF << 56 };
+        weights::weighted(self.xor & (first | 0x00FE_FEFE_FEFE_FEFE), &weights::DEFAULT)
+    }
+
+    /// The graduated distance with the contribution of the first letter capped.
+    ///
+    /// Transliterations often differ only in the first letter (e.g. "Catherine" and "Katherine",
+    /// or "Yusuf" and "Jusuf"). Since the first byte weighs more than all the other bytes, such
+    /// pairs are far apart. This metric lets the first byte contribute at most
+    /// `initial_penalty_cap` to the distance, while the other bytes are weighted as in `dist`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// let diff = Hash::new("catherine") - Hash::new("katherine");
+    /// # #[cfg(feature = "weights-graduated")]
+    /// assert!(!diff.similar());
+    /// # #[cfg(feature = "weights-graduated")]
+    /// assert!(diff.dist_initial_tolerant(10) < 15);
+    /// ```
+    #[inline]
+    pub fn dist_initial_tolerant(self, initial_penalty_cap: u32) -> u32 {
+        let first = ((self.xor >> 56) as u8).count_ones() * weights::DEFAULT[7];
+        self.dist() - first + first.min(initial_penalty_cap)
+    }
+
+    /// The graduated distance, with a penalty for every phone, which one word has more of.
+    ///
+    /// The XOR of the hashes under-penalizes words of different lengths, as missing phones are
+    /// zero, and often differ in few bits from the phones of the other word (e.g. "comp" and
+    /// "computer" are similar). This adds `per_phone_penalty` times the difference of the phone
+    /// counts to the distance. The counts are obtained by
+    /// [`Hash::new_with_len`](./struct.Hash.html#method.new_with_len).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// let (a, len_a) = Hash::new_with_len("comp");
+    /// let (b, len_b) = Hash::new_with_len("computer");
+    /// assert!((a - b).dist_with_length(len_a, len_b, 4) > (a - b).dist());
+    /// # #[cfg(feature = "weights-graduated")]
+    /// assert!((a - b).similar());
+    /// # #[cfg(feature = "weights-graduated")]
+    /// assert!((a - b).dist_with_length(len_a, len_b, 4) >= 15);
+    /// ```
+    #[inline]
+    pub fn dist_with_length(self, len_a: u8, len_b: u8, per_phone_penalty: u32) -> u32 {
+        let diff = (len_a as i32 - len_b as i32).unsigned_abs();
+        self.dist() + per_phone_penalty * diff
+    }
+
+    /// The graduated distance, if it is within a threshold.
+    ///
+    /// This gives `Some(self.dist())` if the distance is at most `threshold`, and `None`
+    /// otherwise. The distance is summed from the first letter, which weighs the most, to the last
+    /// phone, stopping as soon as the partial sum exceeds the threshold, so a scan, which rejects
+    /// most candidates, skips most of the work.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// # #[cfg(feature = "weights-graduated")]
+    /// assert_eq!((Hash::new("jumbo") - Hash::new("jumpo")).within(15), Some(13));
+    /// # #[cfg(feature = "weights-graduated")]
+    /// assert_eq!((Hash::new("jumbo") - Hash::new("horse")).within(15), None);
+    /// ```
+    #[inline]
+    pub fn within(self, threshold: u32) -> Option<u32> {
+        let mut dist = 0;
+        for i in (0..8).rev() {
+            dist += ((self.xor >> (8 * i)) as u8).count_ones() * weights::DEFAULT[i];
+            if dist > threshold {
+                return None;
+            }
+        }
+
+        Some(dist)
     }
 
     /// The XOR distance.
@@ -131,6 +1140,56 @@ impl Difference {
         self.xor
     }
 
+    /// The XOR of every byte of the hashes.
+    ///
+    /// These are big-endian like [`Hash::unpack`](./struct.Hash.html#method.unpack), so the first
+    /// byte is the difference of the first letters, and the last byte the one of the last phones.
+    /// Custom metrics can be built from these.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// let diff = Hash::new("jumbo") - Hash::new("jumpo");
+    /// assert_eq!(diff.bytes().iter().map(|x| x.count_ones()).sum::<u32>(), diff.hamming());
+    /// ```
+    #[inline]
+    pub fn bytes(self) -> [u8; 8] {
+        self.xor.to_be_bytes()
+    }
+
+    /// Compute all the metrics at once.
+    ///
+    /// This takes the Hamming weight of every byte once, rather than once per metric. See
+    /// [`Metrics`](./struct.Metrics.html).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// let diff = Hash::new("jumbo") - Hash::new("jumpo");
+    /// let metrics = diff.all_metrics();
+    /// assert_eq!((metrics.dist, metrics.hamming), (diff.dist(), diff.hamming()));
+    /// ```
+    pub fn all_metrics(self) -> Metrics {
+        let mut dist = 0;
+        let mut hamming = 0;
+        for (i, &weight) in weights::DEFAULT.iter().enumerate() {
+            let ones = ((self.xor >> (8 * i)) as u8).count_ones();
+            dist += ones * weight;
+            hamming += ones;
+        }
+
+        Metrics {
+            dist,
+            hamming,
+            xor: self.xor,
+            normalized: 1.0 - dist as f64 / weights::MAX_DIST as f64,
+        }
+    }
+
     /// The "flat" Hamming based distance.
     ///
     /// This will let every byte carry the same weight, such that mismatch in the early and later
@@ -148,6 +1207,49 @@ impl Difference {
         self.xor.count_ones()
     }
 
+    /// Get the index of the most significant byte, which differs.
+    ///
+    /// The bytes are indexed from 0 (the last phone) to 7 (the first phone), so this tells whether
+    /// the difference is in the dominating first phone or merely in a later phone. `None` means
+    /// that the hashes are equal.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// assert_eq!((Hash::new("horse") - Hash::new("norse")).top_diff_byte(), Some(7));
+    /// assert_eq!((Hash::new("horse") - Hash::new("horse")).top_diff_byte(), None);
+    /// ```
+    #[inline]
+    pub fn top_diff_byte(self) -> Option<u8> {
+        if self.xor == 0 {
+            None
+        } else {
+            Some(7 - (self.xor.leading_zeros() / 8) as u8)
+        }
+    }
+
+    /// Are the words similar and starting with the same sound?
+    ///
+    /// Many name matching systems require the initial sound to agree. This is `true` if the first
+    /// phones are identical and the graduated distance is below `threshold`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::Hash;
+    ///
+    /// # #[cfg(feature = "weights-graduated")]
+    /// assert!((Hash::new("jumpo") - Hash::new("jumbo")).similar_same_initial(15));
+    /// # #[cfg(feature = "weights-graduated")]
+    /// assert!(!(Hash::new("bannana") - Hash::new("panana")).similar_same_initial(40));
+    /// ```
+    #[inline]
+    pub fn similar_same_initial(self, threshold: u32) -> bool {
+        self.xor >> 56 == 0 && self.dist() < threshold
+    }
+
     /// Does this difference constitute similarity?
     ///
     /// # Examples
@@ -159,10 +1261,132 @@ impl Difference {
     /// ```
     #[inline]
     pub fn similar(self) -> bool {
-        self.dist() < 15
+        self.dist() < weights::SIMILAR
+    }
+
+    /// Classify how closely the two words match.
+    ///
+    /// See [`Match`](enum.Match.html) for the categories.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::{Hash, Match};
+    ///
+    /// assert_eq!((Hash::new("maier") - Hash::new("meyer")).classify(), Match::Exact);
+    /// assert_eq!((Hash::new("kat") - Hash::new("gat")).classify(), Match::Homophone);
+    /// # #[cfg(feature = "weights-graduated")]
+    /// assert_eq!((Hash::new("jumpo") - Hash::new("jumbo")).classify(), Match::Similar);
+    /// # #[cfg(feature = "weights-graduated")]
+    /// assert_eq!((Hash::new("horse") - Hash::new("norse")).classify(), Match::Different);
+    /// ```
+    pub fn classify(self) -> Match {
+        if self.xor == 0 {
+            Match::Exact
+        } else if self.dist_no_discriminant() == 0 {
+            Match::Homophone
+        } else if self.similar() {
+            Match::Similar
+        } else {
+            Match::Different
+        }
+    }
+
+    /// The smaller of the forward and the reverse graduated distance of two words.
+    ///
+    /// The hashes are given as by [`Hash::symmetric`](./struct.Hash.html#method.symmetric). Since
+    /// the forward hash emphasizes the beginning of a word, and the reverse one its ending, words
+    /// differing at either extremity are close under this metric.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::{Difference, Hash};
+    ///
+    /// let (fwd_a, rev_a) = Hash::symmetric("catherine");
+    /// let (fwd_b, rev_b) = Hash::symmetric("katherine");
+    /// # #[cfg(feature = "weights-graduated")]
+    /// assert!(Difference::symmetric_dist(fwd_a, rev_a, fwd_b, rev_b) < (fwd_a - fwd_b).dist());
+    /// ```
+    #[inline]
+    pub fn symmetric_dist(fwd_a: Hash, rev_a: Hash, fwd_b: Hash, rev_b: Hash) -> u32 {
+        (fwd_a - fwd_b).dist().min((rev_a - rev_b).dist())
+    }
+
+    /// The fraction of the phones of a query, which are contained in a target.
+    ///
+    /// As opposed to the distance, this is asymmetric: the phones of the target, which the query
+    /// does not have, are ignored. This makes it suitable for matching abbreviations against full
+    /// words. The non-zero bytes of both hashes, from the first phone to the last, are matched in
+    /// order (as the longest common subsequence), and the number of matched bytes is divided by
+    /// the number of non-zero bytes of the query. A query without any is fully covered.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use eudex::{Difference, Hash};
+    ///
+    /// assert_eq!(Difference::coverage(Hash::new("dept"), Hash::new("department")), 1.0);
+    /// assert!(Difference::coverage(Hash::new("department"), Hash::new("dept")) < 0.5);
+    /// ```
+    pub fn coverage(query: Hash, target: Hash) -> f64 {
+        let bytes = |hash: Hash| -> Vec<u8> {
+            hash.hash.to_be_bytes().iter().cloned().filter(|&x| x != 0).collect()
+        };
+        let query = bytes(query);
+        let target = bytes(target);
+
+        if query.is_empty() {
+            return 1.0;
+        }
+
+        // The longest common subsequence of the prefixes of the query and the target.
+        let mut lcs = [[0u8; 9]; 9];
+        for (i, &a) in query.iter().enumerate() {
+            for (j, &b) in target.iter().enumerate() {
+                lcs[i + 1][j + 1] = if a == b {
+                    lcs[i][j] + 1
+                } else {
+                    lcs[i][j + 1].max(lcs[i + 1][j])
+                };
+            }
+        }
+
+        lcs[query.len()][target.len()] as f64 / query.len() as f64
     }
 }
 
+/// All the metrics of a difference.
+///
+/// This is computed by [`Difference::all_metrics`](./struct.Difference.html#method.all_metrics).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Metrics {
+    /// The graduated distance (see `Difference::dist`).
+    pub dist: u32,
+    /// The flat Hamming distance (see `Difference::hamming`).
+    pub hamming: u32,
+    /// The XOR (see `Difference::xor`).
+    pub xor: u64,
+    /// The normalized similarity (see `Difference::normalized`).
+    pub normalized: f64,
+}
+
+/// A category of how closely two words match.
+///
+/// The categories are ordered from closest to farthest.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Match {
+    /// The hashes are equal.
+    Exact,
+    /// The hashes differ only in the discriminant bits, i.e. only in the voicing and similar
+    /// properties that set otherwise equal sounds apart.
+    Homophone,
+    /// The words are similar (as defined by `Difference::similar`).
+    Similar,
+    /// The words are not similar.
+    Different,
+}
+
 /// Deprecated, do not use.
 #[deprecated]
 pub fn similar(a: &str, b: &str) -> bool {
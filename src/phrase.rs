@@ -0,0 +1,315 @@
+//! Phonetic comparison of phrases.
+
+use std::collections::HashSet;
+use std::iter;
+
+use {raw, Hash};
+
+/// The maximal number of tokens of the shorter phrase, for which the assignment is exact.
+const EXACT_TOKENS: usize = 10;
+
+/// The minor words, which initialisms may leave out.
+const MINOR_WORDS: [&str; 9] = ["a", "an", "and", "for", "in", "of", "on", "the", "to"];
+
+/// Split a phrase into its words.
+///
+/// The words are the maximal runs of alphanumeric characters and apostrophes, such that
+/// whitespace and other punctuation separate words.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::phrase::tokens;
+///
+/// assert_eq!(tokens("O'Brien, Mary-Ann").collect::<Vec<_>>(), ["O'Brien", "Mary", "Ann"]);
+/// ```
+pub fn tokens<'a>(phrase: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    phrase
+        .split(|x: char| !x.is_alphanumeric() && x != '\'')
+        .filter(|x| !x.is_empty())
+}
+
+/// Split a word at its camel case boundaries.
+///
+/// A boundary is a lowercase letter followed by an uppercase letter, so e.g. identifiers and
+/// hashtags are split into their words.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::phrase::split_camel_case;
+///
+/// assert_eq!(split_camel_case("getUserName").collect::<Vec<_>>(), ["get", "User", "Name"]);
+/// assert_eq!(split_camel_case("HTTPServer").collect::<Vec<_>>(), ["HTTPServer"]);
+/// ```
+pub fn split_camel_case<'a>(word: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    let mut rest = word;
+
+    iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+
+        let mut prev_lowercase = false;
+        let end = rest
+            .char_indices()
+            .find(|&(_, x)| {
+                let boundary = prev_lowercase && x.is_uppercase();
+                prev_lowercase = x.is_lowercase();
+                boundary
+            })
+            .map_or(rest.len(), |(i, _)| i);
+
+        let (word, tail) = rest.split_at(end);
+        rest = tail;
+        Some(word)
+    })
+}
+
+/// Does a string of initials abbreviate a phrase?
+///
+/// The phrase is split into [`tokens`](./fn.tokens.html), and the phone of the first letter of
+/// every token (the one of the first character of a hash, see
+/// [`raw::injective_phone`](../raw/fn.injective_phone.html)) must match the phones of the letters
+/// of the initials in order. Case and anything other than letters in the initials (e.g. the dots
+/// of "U.S.A.") are ignored. The minor words "a", "an", "and", "for", "in", "of", "on", "the" and
+/// "to" may be either left out or included (e.g. both "DoJ" and "DJ" match "Department of
+/// Justice"). Initials without letters match nothing.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::phrase::matches_initialism;
+///
+/// assert!(matches_initialism("USA", "United States of America"));
+/// assert!(!matches_initialism("USA", "United Kingdom"));
+/// ```
+pub fn matches_initialism(initials: &str, phrase: &str) -> bool {
+    let initials: Vec<u8> = initials.bytes().filter_map(raw::injective_phone).collect();
+    if initials.is_empty() {
+        return false;
+    }
+
+    // Can the first `j` initials be matched by the tokens so far?
+    let mut matched = vec![false; initials.len() + 1];
+    matched[0] = true;
+    for token in tokens(phrase) {
+        let phone = raw::injective_phone(token.as_bytes()[0]);
+        let minor = MINOR_WORDS.iter().any(|x| x.eq_ignore_ascii_case(token));
+
+        let mut next = vec![false; initials.len() + 1];
+        for j in (0..=initials.len()).filter(|&j| matched[j]) {
+            if minor {
+                next[j] = true;
+            }
+            if j < initials.len() && phone == Some(initials[j]) {
+                next[j + 1] = true;
+            }
+        }
+        matched = next;
+    }
+
+    matched[initials.len()]
+}
+
+/// The cost of leaving a word unmatched.
+///
+/// This is its graduated distance to the empty word.
+fn unmatched(hash: Hash) -> u32 {
+    (hash - Hash::new("")).dist()
+}
+
+/// The phonetic distance between two phrases.
+///
+/// Both phrases are split into [`tokens`](./fn.tokens.html), which are then assigned to each
+/// other, such that the total graduated distance is minimal. A word, which is not assigned to any
+/// word of the other phrase, costs its distance to the empty word. In contrast to hashing the
+/// phrases as a whole, this is insensitive to the order of the words.
+///
+/// The assignment is exact when the shorter phrase has at most 10 words, taking
+/// O(n·m·2ᵐ) time, where m is the number of words of the shorter phrase. Otherwise, an
+/// approximation greedily assigning the closest pairs is used.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::phrase::phrase_distance;
+///
+/// assert_eq!(phrase_distance("John Smith", "smith, john"), 0);
+/// assert!(phrase_distance("John Smith", "Jon Smyth") < phrase_distance("John Smith", "Joan Fish"));
+/// ```
+pub fn phrase_distance(a: &str, b: &str) -> u32 {
+    let a: Vec<Hash> = tokens(a).map(Hash::new).collect();
+    let b: Vec<Hash> = tokens(b).map(Hash::new).collect();
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+
+    if short.len() <= EXACT_TOKENS {
+        exact(&long, &short)
+    } else {
+        greedy(&long, &short)
+    }
+}
+
+/// The phonetic signature of a document.
+///
+/// This is the set of the distinct hashes of the [`tokens`](./fn.tokens.html) of the document.
+/// Since misspellings of a word usually share its hash, the signatures of two documents can be
+/// compared as sets (see [`document_similarity`](./fn.document_similarity.html)), tolerating
+/// misspellings.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::phrase::document_signature;
+///
+/// assert_eq!(document_signature("Meyer met Maier"), document_signature("maier met meyer"));
+/// assert_eq!(document_signature("Meyer met Maier").len(), 2);
+/// ```
+pub fn document_signature(text: &str) -> HashSet<Hash> {
+    document_signature_filtered(text, &HashSet::new())
+}
+
+/// The phonetic signature of a document, leaving out stop words.
+///
+/// This is [`document_signature`](./fn.document_signature.html), except that the tokens, whose
+/// lowercased form is one of `stop_words`, are skipped before hashing. Common function words
+/// (e.g. "the", "and", or "of") are phonetic noise, so leaving them out focuses the signature on
+/// the content words. The stop words are expected to be lowercase.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::collections::HashSet;
+/// use eudex::phrase::document_signature_filtered;
+///
+/// let stop_words: HashSet<&str> = ["the", "of"].iter().cloned().collect();
+/// assert_eq!(document_signature_filtered("The Lord of the Rings", &stop_words).len(), 2);
+/// ```
+pub fn document_signature_filtered(text: &str, stop_words: &HashSet<&str>) -> HashSet<Hash> {
+    tokens(text)
+        .filter(|x| stop_words.is_empty() || !stop_words.contains(&x.to_lowercase()[..]))
+        .map(Hash::new)
+        .collect()
+}
+
+/// The phonetic similarity of two documents.
+///
+/// This is the Jaccard index of the [signatures](./fn.document_signature.html) of the documents:
+/// the number of hashes they share, divided by the number of hashes in either. It is between 0
+/// and 1, and two documents without words are considered equal.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::phrase::document_similarity;
+///
+/// assert_eq!(document_similarity("the jumbo jet", "teh jumbo jett"), 1.0);
+/// assert_eq!(document_similarity("jumbo", "horse"), 0.0);
+/// ```
+pub fn document_similarity(a: &str, b: &str) -> f64 {
+    document_similarity_filtered(a, b, &HashSet::new())
+}
+
+/// The phonetic similarity of two documents, leaving out stop words.
+///
+/// This is [`document_similarity`](./fn.document_similarity.html) of the
+/// [filtered signatures](./fn.document_signature_filtered.html).
+///
+/// # Examples
+///
+/// ```rust
+/// use std::collections::HashSet;
+/// use eudex::phrase::{document_similarity, document_similarity_filtered};
+///
+/// let stop_words: HashSet<&str> = ["a", "the"].iter().cloned().collect();
+/// assert!(document_similarity("a jumbo jet", "the jumbo jet") < 1.0);
+/// assert_eq!(document_similarity_filtered("a jumbo jet", "the jumbo jet", &stop_words), 1.0);
+/// ```
+pub fn document_similarity_filtered(a: &str, b: &str, stop_words: &HashSet<&str>) -> f64 {
+    let a = document_signature_filtered(a, stop_words);
+    let b = document_signature_filtered(b, stop_words);
+
+    match a.union(&b).count() {
+        0 => 1.0,
+        union => a.intersection(&b).count() as f64 / union as f64,
+    }
+}
+
+/// Find the optimal assignment by dynamic programming over the used words of `short`.
+fn exact(long: &[Hash], short: &[Hash]) -> u32 {
+    let mut costs = vec![u32::MAX; 1 << short.len()];
+    costs[0] = 0;
+
+    for &x in long {
+        let mut next = vec![u32::MAX; costs.len()];
+
+        for (used, &cost) in costs.iter().enumerate() {
+            if cost == u32::MAX {
+                continue;
+            }
+
+            // Leave `x` unmatched.
+            let unmatched_cost = cost + unmatched(x);
+            if unmatched_cost < next[used] {
+                next[used] = unmatched_cost;
+            }
+
+            // Match `x` with an unused word.
+            for (i, &y) in short.iter().enumerate() {
+                if used & 1 << i == 0 {
+                    let matched = cost + (x - y).dist();
+                    if matched < next[used | 1 << i] {
+                        next[used | 1 << i] = matched;
+                    }
+                }
+            }
+        }
+
+        costs = next;
+    }
+
+    costs
+        .iter()
+        .enumerate()
+        .filter(|&(_, &cost)| cost != u32::MAX)
+        .map(|(used, &cost)| {
+            cost + short
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| used & 1 << i == 0)
+                .map(|(_, &y)| unmatched(y))
+                .sum::<u32>()
+        })
+        .min()
+        .unwrap_or(0)
+}
+
+/// Approximate the optimal assignment by repeatedly matching the closest pair.
+fn greedy(long: &[Hash], short: &[Hash]) -> u32 {
+    let mut pairs = Vec::with_capacity(long.len() * short.len());
+    for (i, &x) in long.iter().enumerate() {
+        for (j, &y) in short.iter().enumerate() {
+            pairs.push(((x - y).dist(), i, j));
+        }
+    }
+    pairs.sort();
+
+    let mut long_used = vec![false; long.len()];
+    let mut short_used = vec![false; short.len()];
+    let mut cost = 0;
+    for (dist, i, j) in pairs {
+        if !long_used[i] && !short_used[j] {
+            long_used[i] = true;
+            short_used[j] = true;
+            cost += dist;
+        }
+    }
+
+    // Every word of `short` is matched, since it is not longer.
+    cost + long
+        .iter()
+        .zip(long_used)
+        .filter(|&(_, used)| !used)
+        .map(|(&x, _)| unmatched(x))
+        .sum::<u32>()
+}
@@ -1,5 +1,12 @@
 //! The raw Eudex API.
 
+use std::{mem, slice};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(all(feature = "tables", feature = "serde"))]
+use serde::{de, Deserializer, Serializer};
+
 /// The sound table.
 ///
 /// The first bit each describes a certain property of the phone:
@@ -153,6 +160,7 @@ const INJECTIVE_PHONES: [u8; LETTERS as usize] = [
 /// Non-ASCII injective phone table.
 ///
 /// Starting at C1.
+#[allow(clippy::eq_op)]
 const INJECTIVE_PHONES_C1: [u8; LETTERS_C1 as usize] = [
     INJECTIVE_PHONES[(b's' - b'a') as usize] ^ 1, // ß
     INJECTIVE_PHONES[(b'a' - b'a') as usize] ^ 1, // à
@@ -204,6 +212,121 @@ const LETTERS: u8 =  26;
 /// Number of letters in our C1 phone map.
 const LETTERS_C1: u8 =  33;
 
+/// Do the table lengths match the number of letters and the width of the C1 range (ß to ÿ)?
+pub(crate) const fn tables_consistent(phones: usize, phones_c1: usize, injective: usize, injective_c1: usize) -> bool {
+    phones == LETTERS as usize
+        && injective == LETTERS as usize
+        && phones_c1 == LETTERS_C1 as usize
+        && injective_c1 == LETTERS_C1 as usize
+        && 0xFF - 0xDF + 1 == LETTERS_C1 as usize
+}
+
+const _: () = assert!(
+    tables_consistent(PHONES.len(), PHONES_C1.len(), INJECTIVE_PHONES.len(), INJECTIVE_PHONES_C1.len()),
+    "the phone tables do not match the letter counts"
+);
+
+/// A copy of the phone tables and their constants.
+///
+/// This is for ports of Eudex to other languages, which need the exact tables, and is created by
+/// [`dump_tables`](./fn.dump_tables.html). It requires the `tables` feature, and can be
+/// (de)serialized with the `serde` feature, e.g. to export the tables as JSON.
+#[cfg(feature = "tables")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct TablesData {
+    /// The trailing phones of the ASCII letters, from a to z.
+    pub phones: [u8; LETTERS as usize],
+    /// The trailing phones of the C1 letters, from ß (U+00DF) to ÿ (U+00FF).
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_c1", deserialize_with = "deserialize_c1"))]
+    pub phones_c1: [u8; LETTERS_C1 as usize],
+    /// The phones of the ASCII letters as the first letter, from a to z.
+    pub injective_phones: [u8; LETTERS as usize],
+    /// The phones of the C1 letters as the first letter, from ß (U+00DF) to ÿ (U+00FF).
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_c1", deserialize_with = "deserialize_c1"))]
+    pub injective_phones_c1: [u8; LETTERS_C1 as usize],
+    /// The length mark of a vowel (see [`VOWEL_LENGTH`](./constant.VOWEL_LENGTH.html)).
+    pub vowel_length: u8,
+}
+
+#[cfg(feature = "tables")]
+impl TablesData {
+    /// Render the tables as CSV.
+    ///
+    /// The header is `letter,phone,injective_phone`, followed by a row of every ASCII letter and
+    /// then every C1 letter, with the phones in decimal.
+    pub fn to_csv(&self) -> String {
+        let ascii = self.phones.iter().zip(&self.injective_phones);
+        let c1 = self.phones_c1.iter().zip(&self.injective_phones_c1);
+        let letters = (b'a'..=b'z').chain(0xDF..=0xFF).map(|x| x as char);
+
+        let mut csv = String::from("letter,phone,injective_phone\n");
+        for (letter, (phone, injective)) in letters.zip(ascii.chain(c1)) {
+            csv += &format!("{},{},{}\n", letter, phone, injective);
+        }
+
+        csv
+    }
+}
+
+/// Serialize a table of the C1 letters, which is too long for the array impls of serde.
+#[cfg(all(feature = "tables", feature = "serde"))]
+fn serialize_c1<S: Serializer>(table: &[u8; LETTERS_C1 as usize], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(table)
+}
+
+/// Deserialize a table of the C1 letters, rejecting tables of the wrong length.
+#[cfg(all(feature = "tables", feature = "serde"))]
+fn deserialize_c1<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; LETTERS_C1 as usize], D::Error> {
+    let table = Vec::<u8>::deserialize(deserializer)?;
+    if table.len() != LETTERS_C1 as usize {
+        return Err(de::Error::invalid_length(table.len(), &"a phone of every C1 letter"));
+    }
+
+    let mut res = [0; LETTERS_C1 as usize];
+    res.copy_from_slice(&table);
+    Ok(res)
+}
+
+/// Copy the phone tables.
+///
+/// This requires the `tables` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::raw;
+///
+/// let tables = raw::dump_tables();
+/// assert_eq!(tables.injective_phones[0], raw::map_first(b'a'));
+/// assert!(tables.to_csv().starts_with("letter,phone,injective_phone\na,0,132\n"));
+/// ```
+#[cfg(feature = "tables")]
+pub fn dump_tables() -> TablesData {
+    TablesData {
+        phones: PHONES,
+        phones_c1: PHONES_C1,
+        injective_phones: INJECTIVE_PHONES,
+        injective_phones_c1: INJECTIVE_PHONES_C1,
+        vowel_length: VOWEL_LENGTH,
+    }
+}
+
+/// The letters of ASCII and the C1 range (ß to ÿ), whose injective phone is `phone`.
+///
+/// This inverts the injective phone tables, with the letters in the order of Latin-1.
+pub(crate) fn first_letters(phone: u8) -> impl Iterator<Item = char> {
+    let ascii = (b'a'..=b'z').zip(INJECTIVE_PHONES.iter());
+    let c1 = (0xDF..=0xFF).zip(INJECTIVE_PHONES_C1.iter());
+
+    ascii
+        .chain(c1)
+        // The ÷ has no phone.
+        .filter(move |&(x, &injective)| injective == phone && x != 0xF7)
+        .map(|(x, _)| x as char)
+}
+
 /// Map the first character in a word.
 #[inline(always)]
 pub fn map_first(mut x: u8) -> u8 {
@@ -212,7 +335,7 @@ pub fn map_first(mut x: u8) -> u8 {
 
     if x < LETTERS {
         INJECTIVE_PHONES[x as usize]
-    } else if x >= 0xDF && x < 0xFF {
+    } else if (0xDF..0xFF).contains(&x) {
         INJECTIVE_PHONES_C1[(x - 0xDF) as usize]
     } else {
         0
@@ -225,19 +348,862 @@ pub fn map_first(mut x: u8) -> u8 {
 ///
 /// Eudex works by building up a hash by this filter and then XORing to get the difference.
 #[inline(always)]
-pub fn filter(prev: u8, mut x: u8) -> Option<u8> {
+pub fn filter(prev: u8, x: u8) -> Option<u8> {
+    let x = phone(x)?;
+
+    if x & 1 != prev & 1 {
+        Some(x)
+    } else { None }
+}
+
+/// Why a character is skipped by [`filter`](./fn.filter.html).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Skip {
+    /// The character has no phone.
+    NoPhone,
+    /// The discriminant of the phone repeats the one of the previous phone.
+    Duplicate,
+}
+
+/// Filter a non-head character, explaining why it is skipped.
+///
+/// This is equivalent to [`filter`](./fn.filter.html), except that a skipped character gives the
+/// reason.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::raw::{self, Skip};
+///
+/// assert_eq!(raw::filter_explained(0, b'-'), Err(Skip::NoPhone));
+/// assert_eq!(raw::filter_explained(0, b'a'), Err(Skip::Duplicate));
+/// assert_eq!(raw::filter_explained(0, b'k'), Ok(0b00001001));
+/// ```
+pub fn filter_explained(prev: u8, x: u8) -> Result<u8, Skip> {
+    let x = phone(x).ok_or(Skip::NoPhone)?;
+
+    if x & 1 != prev & 1 {
+        Ok(x)
+    } else {
+        Err(Skip::Duplicate)
+    }
+}
+
+/// The fraction of the phones of a string, which are dropped as duplicates.
+///
+/// Every character but the first, which has a phone, is a candidate, and this is the fraction of
+/// the candidates skipped as [duplicates](./enum.Skip.html#variant.Duplicate) (or 0 if there are
+/// none). The phone budget of the hash is not taken into account. Words with a high ratio lose
+/// much of their information in the hash.
+///
+/// Note that the deduplication compares only the discriminants, so the ratio reflects runs of
+/// phones with the same discriminant (e.g. the vowels and nasals of "balloon") rather than
+/// repeated letters.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::raw;
+///
+/// assert_eq!(raw::dedup_ratio("balloon"), 1.0);
+/// assert!(raw::dedup_ratio("lizard") < 0.5);
+/// ```
+pub fn dedup_ratio(string: &str) -> f64 {
+    let mut prev = 0;
+    let mut candidates = 0;
+    let mut duplicates = 0;
+
+    for &x in string.as_bytes().iter().skip(1) {
+        match filter_explained(prev, x) {
+            Ok(x) => {
+                prev = x;
+                candidates += 1;
+            }
+            Err(Skip::Duplicate) => {
+                candidates += 1;
+                duplicates += 1;
+            }
+            Err(Skip::NoPhone) => (),
+        }
+    }
+
+    if candidates == 0 {
+        0.0
+    } else {
+        duplicates as f64 / candidates as f64
+    }
+}
+
+/// Filter a non-head character without dropping repeated phones.
+///
+/// Where [`filter`](./fn.filter.html) skips a phone, because its discriminant repeats the one of
+/// `prev`, this nudges `prev` instead, by incrementing the bits above its discriminant. Hence,
+/// `Some(x)` with `x & 1 != prev & 1` means "push x", whereas `Some(x)` with `x & 1 == prev & 1`
+/// means "replace the previous phone by x". `None` means "skip this character", as it has no
+/// phone.
+#[inline]
+pub fn filter_soft(prev: u8, x: u8) -> Option<u8> {
+    Some(nudge(prev, phone(x)?))
+}
+
+/// Push a phone, or nudge the previous phone if the discriminant repeats.
+#[inline(always)]
+fn nudge(prev: u8, x: u8) -> u8 {
+    if x & 1 != prev & 1 {
+        x
+    } else {
+        prev.wrapping_add(2)
+    }
+}
+
+/// Get the (trailing) phone of a character.
+///
+/// `None` means that the character has no phone.
+#[inline(always)]
+pub fn phone(mut x: u8) -> Option<u8> {
     x |= 32;
     x = x.wrapping_sub(b'a');
 
-    x = if x < LETTERS {
-        PHONES[x as usize]
-    } else if x >= 0xDF && x < 0xFF {
-        PHONES_C1[(x - 0xDF) as usize]
+    if x < LETTERS {
+        Some(PHONES[x as usize])
+    } else if (0xDF..0xFF).contains(&x) {
+        Some(PHONES_C1[(x - 0xDF) as usize])
     } else {
-        return None;
+        None
+    }
+}
+
+/// Get the injective phone of a character.
+///
+/// Unlike [`phone`](./fn.phone.html), every letter has its own phone (the one used for the first
+/// character, see [`map_first`](./fn.map_first.html)). `None` means that the character is not a
+/// letter.
+#[inline(always)]
+pub fn injective_phone(mut x: u8) -> Option<u8> {
+    x |= 32;
+    x = x.wrapping_sub(b'a');
+
+    if x < LETTERS {
+        Some(INJECTIVE_PHONES[x as usize])
+    } else if (0xDF..0xFF).contains(&x) {
+        Some(INJECTIVE_PHONES_C1[(x - 0xDF) as usize])
+    } else {
+        None
+    }
+}
+
+/// Get the letter of an IPA symbol.
+///
+/// This gives the index of the letter with the closest phone in the phone tables: `0..26` are the
+/// ASCII letters, and the C1 letters follow. Affricates must be given as the ligatures ʧ and ʤ.
+fn ipa_letter(x: char) -> Option<usize> {
+    let letter = match x {
+        'a' | 'ɑ' | 'æ' | 'ɐ' | 'ɒ' | 'ʌ' | 'ɶ' => b'a',
+        'b' | 'β' => b'b',
+        'ʧ' | 'ʨ' => b'c',
+        'd' | 'ɖ' => b'd',
+        'e' | 'ɛ' | 'ə' | 'ɜ' | 'ɘ' | 'ɞ' | 'œ' => b'e',
+        'f' | 'ɸ' => b'f',
+        'g' | 'ɡ' | 'ɢ' | 'ɣ' => b'g',
+        'h' | 'ɦ' | 'x' | 'χ' | 'ħ' => b'h',
+        'i' | 'ɪ' | 'ɨ' => b'i',
+        'ʤ' | 'ʥ' | 'ʝ' => b'j',
+        'k' | 'c' => b'k',
+        'l' | 'ɫ' | 'ɭ' | 'ʎ' | 'ʟ' => b'l',
+        'm' | 'ɱ' => b'm',
+        'n' | 'ŋ' | 'ɲ' | 'ɳ' | 'ɴ' => b'n',
+        'o' | 'ɔ' | 'ɵ' | 'ɤ' => b'o',
+        'p' => b'p',
+        'q' => b'q',
+        'r' | 'ɹ' | 'ɾ' | 'ʁ' | 'ʀ' | 'ɽ' | 'ɻ' => b'r',
+        's' | 'ʃ' | 'ɕ' | 'ʂ' => b's',
+        't' | 'ʈ' => b't',
+        'u' | 'ʊ' | 'ɯ' | 'ʉ' => b'u',
+        'v' | 'ʋ' => b'v',
+        'w' | 'ʍ' | 'ɰ' => b'w',
+        'j' | 'y' | 'ʏ' | 'ø' => b'y',
+        'z' | 'ʒ' | 'ʑ' | 'ʐ' => b'z',
+        // Represented as ð, a non-plosive T.
+        'θ' | 'ð' => return Some(LETTERS as usize + 0xF0 - 0xDF),
+        _ => return None,
     };
 
-    if x & 1 != prev & 1 {
+    Some((letter - b'a') as usize)
+}
+
+/// Map the first IPA symbol of a transcription.
+///
+/// This is the IPA counterpart of [`map_first`](./fn.map_first.html). Symbols without a phone are
+/// mapped to zero.
+pub fn ipa_map_first(x: char) -> u8 {
+    match ipa_letter(x) {
+        Some(i) if i < LETTERS as usize => INJECTIVE_PHONES[i],
+        Some(i) => INJECTIVE_PHONES_C1[i - LETTERS as usize],
+        None => 0,
+    }
+}
+
+/// Get the (trailing) phone of an IPA symbol.
+///
+/// This is the IPA counterpart of [`phone`](./fn.phone.html). `None` means that the symbol has no
+/// phone (e.g. stress and length marks).
+pub fn ipa_phone(x: char) -> Option<u8> {
+    match ipa_letter(x)? {
+        i if i < LETTERS as usize => Some(PHONES[i]),
+        i => Some(PHONES_C1[i - LETTERS as usize]),
+    }
+}
+
+/// An iterator over the phones of a string.
+///
+/// This is created by [`phones`](./fn.phones.html).
+#[derive(Clone, Debug)]
+pub struct Phones<'a> {
+    bytes: slice::Iter<'a, u8>,
+}
+
+impl<'a> Iterator for Phones<'a> {
+    type Item = u8;
+
+    #[inline]
+    fn next(&mut self) -> Option<u8> {
+        self.bytes.by_ref().filter_map(|&x| phone(x)).next()
+    }
+}
+
+/// Get the phones of every character of a string.
+///
+/// Every character with a phone is included, such that neither duplicates nor the first character
+/// are filtered, as opposed to the hash.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::raw;
+///
+/// assert_eq!(raw::phones("o-k").collect::<Vec<_>>(), [0, 0b00001001]);
+/// ```
+#[inline]
+pub fn phones<'a>(string: &'a str) -> Phones<'a> {
+    Phones {
+        bytes: string.as_bytes().iter(),
+    }
+}
+
+/// Render how a string is heard, approximately.
+///
+/// This is a lossy, human-readable rendering of the phones, which make it into the hash: the first
+/// letter, followed by the phones kept by [`filter`](./fn.filter.html) (at most eight), separated
+/// by dashes. Every consonant phone is rendered as the letter with that phone, whereas the vowel
+/// phones are rendered as "a" (open) and "i" (close). Since duplicates are dropped by their
+/// discriminant, letters can disappear, which is exactly what this is meant to explain.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::raw;
+///
+/// // The b is dropped, as it has the same discriminant as the m.
+/// assert_eq!(raw::transcribe("Jumbo"), "j-i-m");
+/// ```
+pub fn transcribe(string: &str) -> String {
+    let mut res = String::new();
+    let bytes = string.as_bytes();
+    if let Some(&first) = bytes.first() {
+        if first.is_ascii_alphabetic() {
+            res.push((first | 32) as char);
+        }
+    }
+
+    let mut prev = 0;
+    let mut pushed = 0;
+    for &x in bytes.iter().skip(1) {
+        if pushed == 8 {
+            break;
+        }
+
+        if let Some(x) = filter(prev, x) {
+            prev = x;
+            pushed += 1;
+
+            let letter = match x {
+                0 => b'a',
+                1 => b'i',
+                x => PHONES.iter().position(|&y| y == x).map_or(b'?', |i| b'a' + i as u8),
+            };
+            if !res.is_empty() {
+                res.push('-');
+            }
+            res.push(letter as char);
+        }
+    }
+
+    res
+}
+
+/// Get the phonetic properties, which occur in a string.
+///
+/// This is the union of the properties (see the [`PHONES`](./const.PHONES.html) table) of the
+/// [`phones`](./fn.phones.html) of the string, such that each bit is set if some character has
+/// the property. The discriminant is not a property, and is always zero.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::raw;
+///
+/// // The nasal bit.
+/// assert!(raw::property_mask("banana") & 0b10 != 0);
+/// assert!(raw::property_mask("kit") & 0b10 == 0);
+/// ```
+pub fn property_mask(string: &str) -> u8 {
+    phones(string).fold(0, |mask, x| mask | x) & !1
+}
+
+/// The dominant phonetic character of a word.
+///
+/// See [`shape`](./fn.shape.html).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Shape {
+    /// Mostly plosives.
+    Plosive,
+    /// Mostly fricatives.
+    Fricative,
+    /// Mostly nasals.
+    Nasal,
+    /// Mostly liquids.
+    Liquid,
+    /// Mostly vowels.
+    VowelHeavy,
+    /// No dominant property.
+    Mixed,
+}
+
+/// Classify a string by the property dominating its phones.
+///
+/// The string is [`VowelHeavy`](./enum.Shape.html#variant.VowelHeavy), if more than two thirds
+/// of its [`phones`](./fn.phones.html) are vowels (the phones 0 and 1, which w shares). Otherwise,
+/// every consonant counts towards each of its properties (e.g. t is both a fricative and a
+/// plosive in the phone table), and a property dominates if it is held by more than half of the
+/// consonants and by more consonants than any other property. If no property dominates, or the
+/// string has no phones, it is [`Mixed`](./enum.Shape.html#variant.Mixed).
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::raw::{self, Shape};
+///
+/// assert_eq!(raw::shape("banana"), Shape::Nasal);
+/// assert_eq!(raw::shape("lorry"), Shape::Liquid);
+/// ```
+pub fn shape(string: &str) -> Shape {
+    const PROPERTIES: [(u8, Shape); 4] = [
+        (0b00000010, Shape::Nasal),
+        (0b00000100, Shape::Fricative),
+        (0b00001000, Shape::Plosive),
+        (0b00100000, Shape::Liquid),
+    ];
+
+    let mut vowels = 0;
+    let mut consonants = 0;
+    let mut counts = [0; 4];
+    for x in phones(string) {
+        if x >> 1 == 0 {
+            vowels += 1;
+        } else {
+            consonants += 1;
+            for (count, &(bit, _)) in counts.iter_mut().zip(&PROPERTIES) {
+                if x & bit != 0 {
+                    *count += 1;
+                }
+            }
+        }
+    }
+
+    if 3 * vowels > 2 * (vowels + consonants) {
+        return Shape::VowelHeavy;
+    }
+
+    let max = counts.iter().cloned().max().unwrap_or(0);
+    let mut dominant = PROPERTIES.iter().zip(&counts).filter(|&(_, &count)| count == max);
+    match (dominant.next(), dominant.next()) {
+        (Some((&(_, shape), _)), None) if 2 * max > consonants => shape,
+        _ => Shape::Mixed,
+    }
+}
+
+/// A set of phones.
+#[derive(Default)]
+struct PhoneSet {
+    bits: [u64; 4],
+}
+
+impl PhoneSet {
+    /// Collect the phones of a string.
+    fn new(string: &str) -> PhoneSet {
+        let mut set = PhoneSet::default();
+        for x in phones(string) {
+            set.insert(x);
+        }
+
+        set
+    }
+
+    /// Insert a phone.
+    fn insert(&mut self, x: u8) {
+        self.bits[x as usize >> 6] |= 1 << (x & 63);
+    }
+
+    /// Is a phone in the set?
+    fn contains(&self, x: u8) -> bool {
+        self.bits[x as usize >> 6] & 1 << (x & 63) != 0
+    }
+
+    /// The number of phones in the set.
+    fn len(&self) -> u32 {
+        self.bits.iter().map(|x| x.count_ones()).sum()
+    }
+
+    /// The number of phones in both sets.
+    fn intersection(&self, other: &PhoneSet) -> u32 {
+        self.bits.iter().zip(&other.bits).map(|(a, b)| (a & b).count_ones()).sum()
+    }
+
+    /// The number of phones in either set.
+    fn union(&self, other: &PhoneSet) -> u32 {
+        self.bits.iter().zip(&other.bits).map(|(a, b)| (a | b).count_ones()).sum()
+    }
+}
+
+/// The Jaccard index of the phone sets of two strings.
+///
+/// This is the number of distinct phones the strings share, divided by the number of distinct
+/// phones in either, independent of order and position. Two strings without phones are considered
+/// equal.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::raw;
+///
+/// assert_eq!(raw::phone_jaccard("listen", "silent"), 1.0);
+/// assert_eq!(raw::phone_jaccard("map", "kit"), 0.0);
+/// ```
+pub fn phone_jaccard(a: &str, b: &str) -> f64 {
+    let (a, b) = (PhoneSet::new(a), PhoneSet::new(b));
+
+    match a.union(&b) {
+        0 => 1.0,
+        union => a.intersection(&b) as f64 / union as f64,
+    }
+}
+
+/// The phones, which occur in only one of two strings.
+///
+/// This gives the distinct phones only occurring in `a`, and the ones only occurring in `b`, each
+/// in ascending order, which tells exactly what sounds distinguish the strings. See
+/// [`PHONES`](./const.PHONES.html) for the meaning of the bits (and
+/// [`transcribe`](./fn.transcribe.html) for rendering the hashed phones).
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::raw;
+///
+/// let (b, p) = (raw::phones("b").next().unwrap(), raw::phones("p").next().unwrap());
+/// assert_eq!(raw::phone_diff("jumbo", "jumpo"), (vec![b], vec![p]));
+/// ```
+pub fn phone_diff(a: &str, b: &str) -> (Vec<u8>, Vec<u8>) {
+    let (a, b) = (PhoneSet::new(a), PhoneSet::new(b));
+    let only = |x: &PhoneSet, y: &PhoneSet| {
+        (0..=255).filter(|&i| x.contains(i) && !y.contains(i)).collect()
+    };
+
+    (only(&a, &b), only(&b, &a))
+}
+
+/// The cost of inserting or deleting a phone in `phone_edit_distance`.
+///
+/// This is half the number of bits of a phone, so substituting is never costlier than deleting
+/// and inserting.
+const INDEL_COST: u32 = 4;
+
+/// The edit distance of the phones of two strings.
+///
+/// This aligns the [`phones`](./fn.phones.html) of the strings rather than their positions in
+/// the hash, so insertions and deletions (e.g. "schmid" and "schmidt") only cost the phones
+/// involved rather than shifting every following phone. Inserting or deleting a phone costs 4,
+/// and substituting a phone costs the number of bits in which the phones differ. This takes
+/// O(n·m) time in the lengths of the strings.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::raw;
+///
+/// assert_eq!(raw::phone_edit_distance("schmid", "schmidt"), 4);
+/// assert_eq!(raw::phone_edit_distance("jumbo", "jumpo"), 1);
+/// ```
+pub fn phone_edit_distance(a: &str, b: &str) -> u32 {
+    let b: Vec<u8> = phones(b).collect();
+
+    // The costs of the previous row of the table.
+    let mut prev: Vec<u32> = (0..=b.len() as u32).map(|j| j * INDEL_COST).collect();
+    let mut row = Vec::with_capacity(b.len() + 1);
+    for (i, x) in phones(a).enumerate() {
+        row.clear();
+        row.push((i as u32 + 1) * INDEL_COST);
+        for (j, &y) in b.iter().enumerate() {
+            let cost = (prev[j] + (x ^ y).count_ones())
+                .min(prev[j + 1] + INDEL_COST)
+                .min(row[j] + INDEL_COST);
+            row.push(cost);
+        }
+        mem::swap(&mut prev, &mut row);
+    }
+
+    prev[b.len()]
+}
+
+/// Do two strings consist of the same phones, regardless of order?
+///
+/// This compares the phones of the strings as multisets, so every phone must occur equally often
+/// in both (e.g. "listen" and "silent"). Note that this is based on phones rather than letters,
+/// so near-anagrams, whose letters have the same phones (e.g. "mat" and "tom", where a and o are
+/// both open vowels), match as well.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::raw;
+///
+/// assert!(raw::are_phonetic_anagrams("listen", "silent"));
+/// assert!(!raw::are_phonetic_anagrams("listen", "silence"));
+/// ```
+pub fn are_phonetic_anagrams(a: &str, b: &str) -> bool {
+    let mut a: Vec<u8> = phones(a).collect();
+    let mut b: Vec<u8> = phones(b).collect();
+    a.sort_unstable();
+    b.sort_unstable();
+
+    a == b
+}
+
+/// The number of distinct significant phones, for which a string is fully hashable.
+const HASHABLE_PHONES: u32 = 5;
+
+/// Estimate how discriminative the hash of a string is.
+///
+/// This is a number between 0 and 1 based on the number of distinct significant phones (i.e.
+/// consonants rather than vowels) of the string, reaching 1 at five such phones. Very short
+/// strings, strings of only vowels, or mostly punctuation give weak hashes, so matches of them
+/// might deserve less trust.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::raw;
+///
+/// assert!(raw::hashability("rhythm") > raw::hashability("aeiou"));
+/// ```
+pub fn hashability(string: &str) -> f32 {
+    let mut set = PhoneSet::default();
+    for x in phones(string).filter(|&x| x >> 1 != 0) {
+        set.insert(x);
+    }
+
+    set.len().min(HASHABLE_PHONES) as f32 / HASHABLE_PHONES as f32
+}
+
+/// The phone of characters, which are skipped.
+///
+/// This is not a phone of any letter.
+#[cfg(test)]
+const SKIP: u8 = 0b00000011;
+
+/// The combined filter table.
+///
+/// This maps every byte directly to its trailing phone (from `PHONES` or `PHONES_C1`), or `SKIP`
+/// if it has none, such that filtering is a single lookup rather than a branch on the character
+/// class.
+///
+/// The benchmarks show no measurable gain over the branching `filter`, even on mixed input, so
+/// this is only kept for comparison.
+#[cfg(test)]
+const FILTER: [u8; 256] = filter_table();
+
+/// Derive the combined filter table from the phone tables.
+#[cfg(test)]
+const fn filter_table() -> [u8; 256] {
+    let mut table = [SKIP; 256];
+
+    let mut i = 0;
+    while i < 256 {
+        let x = (i as u8 | 32).wrapping_sub(b'a');
+
+        if x < LETTERS {
+            table[i] = PHONES[x as usize];
+        } else if x >= 0xDF && x < 0xFF {
+            table[i] = PHONES_C1[(x - 0xDF) as usize];
+        }
+
+        i += 1;
+    }
+
+    table
+}
+
+/// Filter a non-head character through the combined filter table.
+///
+/// This is equivalent to `filter`.
+#[cfg(test)]
+#[inline(always)]
+pub fn filter_lookup(prev: u8, x: u8) -> Option<u8> {
+    let x = FILTER[x as usize];
+
+    if x != SKIP && x & 1 != prev & 1 {
         Some(x)
     } else { None }
 }
+
+/// The length mark of a vowel.
+///
+/// When vowel length is enabled in [`HashConfig`](../struct.HashConfig.html), these bits are set
+/// on the phone of a vowel, when it is immediately repeated (e.g. the "uu" in "tuuli"). Trailing
+/// vowels only use the discriminant, and no phone of the default tables has both of the two most
+/// significant bits set, so a marked vowel never collides with another phone. Custom tables can
+/// set them, as a confident labial (see [`TableBuilder::confident`](./struct.TableBuilder.html#method.confident))
+/// has both, but every labial has a manner bit as well, so it is still distinct from a marked
+/// vowel, if only by a single bit.
+pub const VOWEL_LENGTH: u8 = 0b11000000;
+
+/// The overlength mark of a vowel.
+///
+/// When vowel runs are enabled in [`HashConfig`](../struct.HashConfig.html), these bits are set
+/// on the phone of a vowel, when it is repeated twice or more (e.g. the "uuu" in "tuuuli"). They
+/// include the bits of [`VOWEL_LENGTH`](./constant.VOWEL_LENGTH.html), and differ from them in a
+/// single bit. As with a long vowel, no phone of the default tables collides with an overlong
+/// vowel, and the confident labials of custom tables differ from it in their manner bits.
+pub const VOWEL_OVERLONG: u8 = VOWEL_LENGTH | 0b00100000;
+
+/// Is this character a vowel?
+///
+/// This is determined by the vowel bit of the injective phone table.
+#[inline(always)]
+pub fn is_vowel(x: u8) -> bool {
+    map_first(x) & 128 != 0
+}
+
+/// The "confident" bit of the phone table.
+const CONFIDENT: u8 = 0b10000000;
+
+/// A set of phone tables for the ASCII letters.
+///
+/// The default tables are the ones used by [`Hash::new`](../struct.Hash.html#method.new). Custom
+/// tables are created through [`TableBuilder`](./struct.TableBuilder.html) and used by passing them
+/// to [`HashConfig::tables`](../struct.HashConfig.html#method.tables).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct Tables {
+    /// The trailing phones.
+    phones: [u8; LETTERS as usize],
+    /// The injective phones of the first character.
+    injective: [u8; LETTERS as usize],
+}
+
+impl Default for Tables {
+    fn default() -> Tables {
+        Tables {
+            phones: PHONES,
+            injective: INJECTIVE_PHONES,
+        }
+    }
+}
+
+impl Tables {
+    /// Map the first character in a word.
+    ///
+    /// Non-ASCII characters are mapped as by [`map_first`](./fn.map_first.html).
+    #[inline]
+    pub fn map_first(&self, x: u8) -> u8 {
+        let i = (x | 32).wrapping_sub(b'a');
+
+        if i < LETTERS {
+            self.injective[i as usize]
+        } else {
+            map_first(x)
+        }
+    }
+
+    /// Filter a non-head character.
+    ///
+    /// Non-ASCII characters are filtered as by [`filter`](./fn.filter.html).
+    #[inline]
+    pub fn filter(&self, prev: u8, x: u8) -> Option<u8> {
+        let x = self.phone(x)?;
+
+        if x & 1 != prev & 1 {
+            Some(x)
+        } else { None }
+    }
+
+    /// Filter a non-head character without dropping repeated phones.
+    ///
+    /// See [`filter_soft`](./fn.filter_soft.html).
+    #[inline]
+    pub fn filter_soft(&self, prev: u8, x: u8) -> Option<u8> {
+        Some(nudge(prev, self.phone(x)?))
+    }
+
+    /// Get the (trailing) phone of a character.
+    #[inline]
+    pub(crate) fn phone(&self, x: u8) -> Option<u8> {
+        let i = (x | 32).wrapping_sub(b'a');
+
+        if i < LETTERS {
+            Some(self.phones[i as usize])
+        } else {
+            phone(x)
+        }
+    }
+}
+
+/// A mapping of characters to phones, as used by the configurable hashing.
+pub(crate) trait PhoneMap {
+    /// Map the first character in a word.
+    fn map_first(&self, x: u8) -> u8;
+
+    /// Get the (trailing) phone of a character.
+    fn phone(&self, x: u8) -> Option<u8>;
+
+    /// Filter a non-head character.
+    #[inline]
+    fn filter(&self, prev: u8, x: u8) -> Option<u8> {
+        let x = self.phone(x)?;
+
+        if x & 1 != prev & 1 {
+            Some(x)
+        } else { None }
+    }
+
+    /// Filter a non-head character without dropping repeated phones.
+    #[inline]
+    fn filter_soft(&self, prev: u8, x: u8) -> Option<u8> {
+        Some(nudge(prev, self.phone(x)?))
+    }
+}
+
+impl PhoneMap for Tables {
+    #[inline]
+    fn map_first(&self, x: u8) -> u8 {
+        Tables::map_first(self, x)
+    }
+
+    #[inline]
+    fn phone(&self, x: u8) -> Option<u8> {
+        Tables::phone(self, x)
+    }
+}
+
+/// Phone tables compiled into lookups of every byte.
+///
+/// This trades the case folding and range checks of [`Tables`](./struct.Tables.html) for a single
+/// lookup per character.
+#[derive(Clone, Debug)]
+pub(crate) struct CompiledTables {
+    /// The phones of the first characters.
+    first: [u8; 256],
+    /// The trailing phones.
+    phones: [Option<u8>; 256],
+}
+
+impl CompiledTables {
+    /// Compile some tables.
+    pub(crate) fn new(tables: &Tables) -> CompiledTables {
+        let mut compiled = CompiledTables {
+            first: [0; 256],
+            phones: [None; 256],
+        };
+        for x in 0..=255 {
+            compiled.first[x as usize] = tables.map_first(x);
+            compiled.phones[x as usize] = tables.phone(x);
+        }
+
+        compiled
+    }
+}
+
+impl PhoneMap for CompiledTables {
+    #[inline]
+    fn map_first(&self, x: u8) -> u8 {
+        self.first[x as usize]
+    }
+
+    #[inline]
+    fn phone(&self, x: u8) -> Option<u8> {
+        self.phones[x as usize]
+    }
+}
+
+/// A builder of custom phone tables.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::{Hash, HashConfig};
+/// use eudex::raw::TableBuilder;
+///
+/// let config = HashConfig::new().tables(TableBuilder::new().confident("lrxzqk").build());
+///
+/// let default = Hash::new("kat") - Hash::new("gat");
+/// let custom = config.build_hash("kat") - config.build_hash("gat");
+/// assert!(custom.dist() > default.dist());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TableBuilder {
+    tables: Tables,
+}
+
+impl TableBuilder {
+    /// Start from the default tables.
+    #[inline]
+    pub fn new() -> TableBuilder {
+        TableBuilder::default()
+    }
+
+    /// Redefine the set of "confident" letters.
+    ///
+    /// These are the letters, which are hard to misspell (by default, l, r, x, z, and q). Since the
+    /// confident bit is the most significant bit of the trailing phones, the choice heavily affects
+    /// the distance.
+    ///
+    /// The consonants of the injective table are a right shifted version of the trailing ones, so
+    /// the confident bit of a consonant is moved to the seventh bit of its injective phone as well.
+    /// This can make the first phones of two consonants collide. Vowels and non-letters in
+    /// `letters` are ignored, since the bit has another meaning for them.
+    pub fn confident(mut self, letters: &str) -> TableBuilder {
+        for i in 0..LETTERS as usize {
+            let letter = b'a' + i as u8;
+            if is_vowel(letter) {
+                continue;
+            }
+
+            if letters.bytes().any(|x| x | 32 == letter) {
+                self.tables.phones[i] |= CONFIDENT;
+                self.tables.injective[i] |= CONFIDENT >> 1;
+            } else {
+                self.tables.phones[i] &= !CONFIDENT;
+                self.tables.injective[i] &= !(CONFIDENT >> 1);
+            }
+        }
+
+        self
+    }
+
+    /// Build the tables.
+    #[inline]
+    pub fn build(self) -> Tables {
+        self.tables
+    }
+}
@@ -0,0 +1,63 @@
+//! A reference implementation of the algorithm.
+//!
+//! This is written in the most straightforward way rather than the fastest, and serves to document
+//! and cross-check the optimized [`Hash::new`](../struct.Hash.html#method.new).
+
+use {raw, Hash};
+
+/// The maximal number of trailing phones.
+const TRAILING_PHONES: usize = 8;
+
+/// Phonetically hash this string.
+///
+/// This is equivalent to [`Hash::new`](../struct.Hash.html#method.new).
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::{reference, Hash};
+///
+/// assert_eq!(reference::hash("computer"), Hash::new("computer"));
+/// ```
+pub fn hash(string: &str) -> Hash {
+    let bytes = string.as_bytes();
+
+    // The first character is mapped through the injective table. The empty string is treated as
+    // a zero byte.
+    let first = raw::map_first(bytes.first().cloned().unwrap_or(0));
+
+    // Collect the trailing phones.
+    let mut phones = Vec::new();
+    for &x in bytes.iter().skip(1) {
+        if phones.len() == TRAILING_PHONES {
+            break;
+        }
+
+        let phone = match raw::phone(x) {
+            Some(phone) => phone,
+            // Skip characters without a phone.
+            None => continue,
+        };
+
+        // Skip the phone if its discriminant equals the one of the previous phone (which is zero
+        // if there is none).
+        let prev = phones.last().cloned().unwrap_or(0);
+        if phone & 1 == prev & 1 {
+            continue;
+        }
+
+        phones.push(phone);
+    }
+
+    // Pack the phones, such that the last one is the least significant byte.
+    let mut trailing = 0u64;
+    for phone in phones {
+        trailing = trailing << 8 | phone as u64;
+    }
+
+    // The first phone occupies the most significant byte. With eight trailing phones, the first
+    // of them shares that byte.
+    Hash {
+        hash: (first as u64) << 56 | trailing,
+    }
+}
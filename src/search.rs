@@ -0,0 +1,818 @@
+//! Searching lists of words.
+
+use std::cmp;
+use std::collections::{HashMap, HashSet};
+
+use {raw, weights, Difference, Hash, Match};
+
+/// The weight of the frequency in `suggest_weighted`, relative to the normalized similarity.
+const FREQUENCY_WEIGHT: f64 = 0.05;
+/// The largest contribution of the first letter to the distance of a pattern's head in
+/// `matches_pattern`.
+const PATTERN_INITIAL_CAP: u32 = 10;
+
+/// Find the pairs of words within some graduated distance.
+///
+/// This gives the pairs `(i, j, dist)` with `i < j`, ordered by `i` and then `j`.
+///
+/// Rather than comparing all the pairs, the words are bucketed by their first byte. Every bit, in
+/// which the first bytes differ, adds the weight of the first byte to the distance (34 under the
+/// graduated weights), so most pairs of buckets can be skipped when the threshold is low.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::search::find_collisions;
+///
+/// let words = ["jumbo", "horse", "jumpo", "meyer", "maier"];
+/// # #[cfg(feature = "weights-graduated")]
+/// assert_eq!(find_collisions(&words, 15), [(0, 2, 13), (3, 4, 0)]);
+/// ```
+pub fn find_collisions(words: &[&str], threshold: u32) -> Vec<(usize, usize, u32)> {
+    let hashes: Vec<Hash> = words.iter().map(|x| Hash::new(x)).collect();
+
+    let mut buckets = vec![Vec::new(); 256];
+    for (i, &hash) in hashes.iter().enumerate() {
+        buckets[(u64::from(hash) >> 56) as usize].push(i);
+    }
+
+    let mut res = Vec::new();
+    for a in 0..256usize {
+        for b in a..256 {
+            if (a ^ b).count_ones() * weights::DEFAULT[7] > threshold {
+                continue;
+            }
+
+            for &i in &buckets[a] {
+                for &j in &buckets[b] {
+                    if a == b && i >= j {
+                        continue;
+                    }
+
+                    let dist = (hashes[i] - hashes[j]).dist();
+                    if dist <= threshold {
+                        res.push((i.min(j), i.max(j), dist));
+                    }
+                }
+            }
+        }
+    }
+
+    res.sort();
+    res
+}
+
+/// Rank hashes by their graduated distance to a query.
+///
+/// This gives the pairs `(i, dist)` ordered by distance, and then by index.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::Hash;
+/// use eudex::search::rank;
+///
+/// let hashes = [Hash::new("horse"), Hash::new("jumbo")];
+/// assert_eq!(rank(Hash::new("jumpo"), &hashes)[0].0, 1);
+/// ```
+pub fn rank(query: Hash, hashes: &[Hash]) -> Vec<(usize, u32)> {
+    let mut res: Vec<(usize, u32)> = hashes.iter().map(|&x| (query - x).dist()).enumerate().collect();
+    res.sort_by_key(|&(i, dist)| (dist, i));
+    res
+}
+
+/// Rank words by their graduated distance to a query.
+///
+/// This is equivalent to [`rank`](./fn.rank.html) over the hashes of the words, which are hashed on
+/// every call. Repeated queries against the same words should hash them once and use `rank`
+/// instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::search::rank_words;
+///
+/// assert_eq!(rank_words("jumpo", &["horse", "jumbo"])[0].0, 1);
+/// ```
+pub fn rank_words(query: &str, words: &[&str]) -> Vec<(usize, u32)> {
+    let hashes: Vec<Hash> = words.iter().map(|x| Hash::new(x)).collect();
+    rank(Hash::new(query), &hashes)
+}
+
+/// Find all the words of a dictionary within some graduated distance of a word.
+///
+/// As opposed to [`rank`](./fn.rank.html), the neighborhood is not ordered by distance, but given
+/// in the order of the dictionary, making it suitable for recall-oriented retrieval (e.g. query
+/// expansion).
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::Hash;
+/// use eudex::search::neighbors;
+///
+/// let dictionary = [(Hash::new("jumbo"), "jumbo"), (Hash::new("horse"), "horse")];
+/// # #[cfg(feature = "weights-graduated")]
+/// assert_eq!(neighbors("jumpo", 15, &dictionary), ["jumbo"]);
+/// ```
+pub fn neighbors<'a>(word: &str, max_dist: u32, dictionary: &[(Hash, &'a str)]) -> Vec<&'a str> {
+    neighbors_by(word, &MaxDist(max_dist), dictionary)
+}
+
+/// Find all the words of a dictionary, which a predicate considers similar to a word.
+///
+/// This is [`neighbors`](./fn.neighbors.html) under an arbitrary
+/// [`SimilarityPredicate`](./trait.SimilarityPredicate.html), in the order of the dictionary.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::Hash;
+/// use eudex::search::{neighbors_by, SameInitial};
+///
+/// let dictionary = [(Hash::new("jumbo"), "jumbo"), (Hash::new("gumbo"), "gumbo")];
+/// # #[cfg(feature = "weights-graduated")]
+/// assert_eq!(neighbors_by("jumpo", &SameInitial(15), &dictionary), ["jumbo"]);
+/// ```
+pub fn neighbors_by<'a, P: SimilarityPredicate + ?Sized>(
+    word: &str,
+    predicate: &P,
+    dictionary: &[(Hash, &'a str)],
+) -> Vec<&'a str> {
+    let hash = Hash::new(word);
+
+    dictionary
+        .iter()
+        .filter(|&&(x, _)| predicate.is_similar(hash - x))
+        .map(|&(_, x)| x)
+        .collect()
+}
+
+/// Find the closest pair of a list of hashes.
+///
+/// This gives `(i, j, dist)` with `i < j` of the pair with the smallest graduated distance
+/// (favoring the smallest indices in case of ties), or `None` if there are less than two hashes.
+///
+/// The hashes are bucketed by their first byte. Pairs within the same bucket are compared first,
+/// after which pairs of buckets, whose first bytes alone are further apart than the closest pair
+/// found, are skipped.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::Hash;
+/// use eudex::search::closest_pair;
+///
+/// let hashes = [Hash::new("jumbo"), Hash::new("horse"), Hash::new("jumpo")];
+/// # #[cfg(feature = "weights-graduated")]
+/// assert_eq!(closest_pair(&hashes), Some((0, 2, 13)));
+/// ```
+pub fn closest_pair(hashes: &[Hash]) -> Option<(usize, usize, u32)> {
+    let mut buckets = vec![Vec::new(); 256];
+    for (i, &hash) in hashes.iter().enumerate() {
+        buckets[(u64::from(hash) >> 56) as usize].push(i);
+    }
+
+    let mut best: Option<(u32, usize, usize)> = None;
+    let visit = |a: &[usize], b: &[usize], same: bool, best: &mut Option<(u32, usize, usize)>| {
+        for &i in a {
+            for &j in b {
+                if same && i >= j {
+                    continue;
+                }
+
+                let candidate = ((hashes[i] - hashes[j]).dist(), i.min(j), i.max(j));
+                if best.map_or(true, |x| candidate < x) {
+                    *best = Some(candidate);
+                }
+            }
+        }
+    };
+
+    for bucket in &buckets {
+        visit(bucket, bucket, true, &mut best);
+    }
+
+    for a in 0..256usize {
+        for b in a + 1..256 {
+            if best.map_or(false, |x| (a ^ b).count_ones() * weights::DEFAULT[7] > x.0) {
+                continue;
+            }
+
+            visit(&buckets[a], &buckets[b], false, &mut best);
+        }
+    }
+
+    best.map(|(dist, i, j)| (i, j, dist))
+}
+
+/// Compute the graduated distances between all pairs of some words.
+///
+/// Entry `[i][j]` of the matrix is the distance between word `i` and word `j`. Each word is hashed
+/// once, and since the distance is symmetric, only the upper triangle is computed, and mirrored.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::search::distance_matrix;
+///
+/// let matrix = distance_matrix(&["jumbo", "jumpo"]);
+/// # #[cfg(feature = "weights-graduated")]
+/// assert_eq!(matrix, [[0, 13], [13, 0]]);
+/// ```
+pub fn distance_matrix(words: &[&str]) -> Vec<Vec<u32>> {
+    let hashes: Vec<Hash> = words.iter().map(|x| Hash::new(x)).collect();
+    let mut res = vec![vec![0; hashes.len()]; hashes.len()];
+
+    for i in 0..hashes.len() {
+        for j in i + 1..hashes.len() {
+            let dist = (hashes[i] - hashes[j]).dist();
+            res[i][j] = dist;
+            res[j][i] = dist;
+        }
+    }
+
+    res
+}
+
+/// Generate hashes close to the hash of a word.
+///
+/// This perturbs the hash by flipping bits, lowest weights first, within the largest distance
+/// considered similar (see [`Difference::similar`](../struct.Difference.html#method.similar)).
+/// The hashes are ordered by their distance to the hash of the word, which itself is not included,
+/// and at most `max_results` are given.
+///
+/// Note that this produces hashes, not spellings: most of the hashes are not the hash of any
+/// word. It is intended for generating test data, e.g. for testing the recall of an index.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::Hash;
+/// use eudex::search::expand;
+///
+/// let hashes = expand("jumbo", 100);
+/// assert_eq!(hashes.len(), 100);
+/// assert!(hashes.iter().all(|&x| (x - Hash::new("jumbo")).similar()));
+/// ```
+pub fn expand(word: &str, max_results: usize) -> Vec<Hash> {
+    /// The largest distance considered similar.
+    const BUDGET: u32 = weights::SIMILAR - 1;
+
+    /// Push the perturbations of the bytes from some byte on, costing exactly `cost`.
+    fn perturb(byte: usize, cost: u32, mask: u64, res: &mut Vec<u64>, max_results: usize) {
+        if res.len() == max_results {
+            return;
+        }
+        if cost == 0 {
+            res.push(mask);
+            return;
+        }
+        if byte == 8 {
+            return;
+        }
+
+        let weight = weights::DEFAULT[byte];
+        for x in 0..=255u8 {
+            let x_cost = x.count_ones() * weight;
+            if x_cost <= cost {
+                perturb(byte + 1, cost - x_cost, mask | (x as u64) << (8 * byte), res, max_results);
+            }
+        }
+    }
+
+    let hash = u64::from(Hash::new(word));
+
+    let mut masks = Vec::new();
+    for cost in 1..=BUDGET {
+        perturb(0, cost, 0, &mut masks, max_results);
+    }
+
+    masks.into_iter().map(|mask| Hash { hash: hash ^ mask }).collect()
+}
+
+/// The affinity of a query to a cluster of hashes.
+///
+/// This is the mean [normalized](../struct.Difference.html#method.normalized) similarity of the
+/// query to the members of the cluster, or 0 if the cluster is empty. A query can be classified
+/// into the cluster of highest affinity.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::Hash;
+/// use eudex::search::cluster_affinity;
+///
+/// let jumbos = [Hash::new("jumbo"), Hash::new("jumpo")];
+/// let horses = [Hash::new("horse"), Hash::new("norse")];
+/// let query = Hash::new("jumbu");
+/// assert!(cluster_affinity(query, &jumbos) > cluster_affinity(query, &horses));
+/// ```
+pub fn cluster_affinity(query: Hash, cluster: &[Hash]) -> f64 {
+    if cluster.is_empty() {
+        return 0.0;
+    }
+
+    cluster.iter().map(|&x| (query - x).normalized()).sum::<f64>() / cluster.len() as f64
+}
+
+/// A blend of the positional and the set similarity of two words.
+///
+/// This is `alpha` times the [normalized](../struct.Difference.html#method.normalized) graduated
+/// similarity, which is sensitive to the order of the phones, plus `1 - alpha` times the
+/// [Jaccard similarity](../raw/fn.phone_jaccard.html) of their phones, which is not. Hence, `alpha`
+/// must be between 0 (only the phones) and 1 (only the hash), and the similarity is between 0 and
+/// 1 too.
+///
+/// # Panics
+///
+/// This panics if `alpha` is not between 0 and 1.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::search::combined_similarity;
+///
+/// assert!(combined_similarity("listen", "silent", 0.0) > combined_similarity("listen", "silent", 1.0));
+/// ```
+pub fn combined_similarity(a: &str, b: &str, alpha: f64) -> f64 {
+    assert!((0.0..=1.0).contains(&alpha), "alpha must be between 0 and 1");
+
+    alpha * (Hash::new(a) - Hash::new(b)).normalized() + (1.0 - alpha) * raw::phone_jaccard(a, b)
+}
+
+/// Fit a distance threshold to labeled pairs of hashes.
+///
+/// The positives are pairs, which should match, and the negatives are pairs, which should not.
+/// Taking a pair with a graduated distance of at most the threshold as a match, this gives the
+/// threshold with the highest F1 score (favoring the lowest threshold in case of ties). Only the
+/// distances of the pairs are candidates, and if there are no positives, this gives 0.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::Hash;
+/// use eudex::search::fit_threshold;
+///
+/// let positives = [(Hash::new("jumbo"), Hash::new("jumpo")), (Hash::new("maier"), Hash::new("meyer"))];
+/// let negatives = [(Hash::new("jumbo"), Hash::new("horse"))];
+/// # #[cfg(feature = "weights-graduated")]
+/// assert_eq!(fit_threshold(&positives, &negatives), 13);
+/// ```
+pub fn fit_threshold(positives: &[(Hash, Hash)], negatives: &[(Hash, Hash)]) -> u32 {
+    // The distances, and whether they are positive.
+    let mut dists: Vec<(u32, bool)> = positives
+        .iter()
+        .map(|&(a, b)| ((a - b).dist(), true))
+        .chain(negatives.iter().map(|&(a, b)| ((a - b).dist(), false)))
+        .collect();
+    dists.sort();
+
+    let mut best = (0.0, 0);
+    let (mut true_positives, mut false_positives) = (0, 0);
+    let mut i = 0;
+    while i < dists.len() {
+        // Take in all the pairs at this distance.
+        let dist = dists[i].0;
+        while i < dists.len() && dists[i].0 == dist {
+            if dists[i].1 {
+                true_positives += 1;
+            } else {
+                false_positives += 1;
+            }
+            i += 1;
+        }
+
+        let false_negatives = positives.len() - true_positives;
+        let f1 = 2.0 * true_positives as f64
+            / (2 * true_positives + false_positives + false_negatives) as f64;
+        if f1 > best.0 {
+            best = (f1, dist);
+        }
+    }
+
+    best.1
+}
+
+/// Does a word match a pattern with a wildcard phonetically?
+///
+/// The pattern may contain a single `*`, which matches any run of characters (including none). The
+/// segments before and after the wildcard are hashed, and the word matches if some prefix of it is
+/// within a distance of `threshold` of the first segment (see below), and some suffix following
+/// that prefix is within a graduated distance of `threshold` of the second segment. The wildcard
+/// is greedy, as it is matched against everything between the shortest such prefix and the
+/// shortest such suffix. An empty segment matches the empty prefix or suffix, and a pattern without wildcard
+/// must match the whole word. A pattern with more than one wildcard matches no word.
+///
+/// The first segment is compared by
+/// [`Difference::dist_initial_tolerant`](../struct.Difference.html#method.dist_initial_tolerant),
+/// where the first letter contributes at most 10, as the first letters of queries are often
+/// transliterated (e.g. "comp*" matches "kompewter" at a threshold of 15).
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::search::matches_pattern;
+///
+/// assert!(matches_pattern("comp*", "computer", 15));
+/// assert!(matches_pattern("*ter", "computer", 15));
+/// # #[cfg(feature = "weights-graduated")]
+/// assert!(matches_pattern("comp*", "kompewter", 15));
+/// assert!(!matches_pattern("comp*", "kompewter", 0));
+/// assert!(!matches_pattern("c*m*r", "computer", 15));
+/// ```
+pub fn matches_pattern(pattern: &str, word: &str, threshold: u32) -> bool {
+    let mut segments = pattern.splitn(2, '*');
+    let head = Hash::new(segments.next().unwrap_or(""));
+    let head_dist = |x: &str| (head - Hash::new(x)).dist_initial_tolerant(PATTERN_INITIAL_CAP);
+    let tail = match segments.next() {
+        Some(tail) if tail.contains('*') => return false,
+        Some(tail) => Hash::new(tail),
+        None => return head_dist(word) <= threshold,
+    };
+
+    // The boundaries of the characters, including the end.
+    let bounds: Vec<usize> = word.char_indices().map(|(i, _)| i).chain(Some(word.len())).collect();
+    // The end of the shortest matching prefix, and the start of the shortest matching suffix.
+    let prefix = bounds.iter().find(|&&i| head_dist(&word[..i]) <= threshold);
+    let suffix = bounds.iter().rev().find(|&&i| (tail - Hash::new(&word[i..])).dist() <= threshold);
+
+    match (prefix, suffix) {
+        (Some(prefix), Some(suffix)) => prefix <= suffix,
+        _ => false,
+    }
+}
+
+/// The graduated distance of two words, at best with their diacritics stripped.
+///
+/// This is the least of the distances of the hashes of the words as they are, and of the hashes
+/// with the diacritics stripped (see
+/// [`Hash::new_normalized`](../struct.Hash.html#method.new_normalized)), so the stripping is only
+/// used where it brings the words closer.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::Hash;
+/// use eudex::search::best_distance;
+///
+/// assert!((Hash::new("Müller") - Hash::new("muller")).dist() > 0);
+/// assert_eq!(best_distance("Müller", "muller"), 0);
+/// ```
+pub fn best_distance(a: &str, b: &str) -> u32 {
+    let raw = (Hash::new(a) - Hash::new(b)).dist();
+    let normalized = (Hash::new_normalized(a) - Hash::new_normalized(b)).dist();
+
+    raw.min(normalized)
+}
+
+/// Find where a query sounds like a substring of a text.
+///
+/// Every window of the text, which has as many characters as the query, is hashed (see
+/// [`Hash::ngrams`](../struct.Hash.html#method.ngrams)), and this gives the byte offsets of the
+/// windows within a graduated distance of `threshold` of the query, in order. Overlapping windows
+/// can match at once. A text shorter than the query is compared as a whole, and an empty query
+/// matches nothing.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::search::find_phonetic;
+///
+/// # #[cfg(feature = "weights-graduated")]
+/// assert_eq!(find_phonetic("jumbo", "a jumpo jet", 15), [2]);
+/// ```
+pub fn find_phonetic(query: &str, text: &str, threshold: u32) -> Vec<usize> {
+    let len = query.chars().count();
+    if len == 0 {
+        return Vec::new();
+    }
+
+    let query = Hash::new(query);
+    text.char_indices()
+        .map(|(i, _)| i)
+        .chain(if text.is_empty() { Some(0) } else { None })
+        .zip(Hash::ngrams(text, len))
+        .filter(|&(_, hash)| (query - hash).dist() <= threshold)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Suggest the words of a weighted dictionary, which a query might mean.
+///
+/// Every entry of the dictionary is a word and its frequency, and is scored as
+///
+/// ```text
+/// similarity + 0.05 · ln(1 + frequency) / ln(1 + max frequency)
+/// ```
+///
+/// where the similarity is the [normalized](../struct.Difference.html#method.normalized)
+/// similarity to the query. Thus, common words rank above rare words at an equal distance, and
+/// above rare words, which are slightly closer, too. This gives the (at most) `max` best entries
+/// and their scores, in descending order of score and then in the order of the dictionary.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::search::suggest_weighted;
+///
+/// let dict = [("jumpo", 1), ("jumbo", 1000), ("horse", 10)];
+/// let suggestions = suggest_weighted("jumbu", &dict, 2);
+/// assert_eq!(suggestions.iter().map(|x| x.0).collect::<Vec<_>>(), ["jumbo", "jumpo"]);
+/// ```
+pub fn suggest_weighted<'a>(query: &str, dict: &[(&'a str, u64)], max: usize) -> Vec<(&'a str, f64)> {
+    let query = Hash::new(query);
+    let max_frequency = dict.iter().map(|&(_, frequency)| frequency).max().unwrap_or(0);
+    let scale = (max_frequency as f64).ln_1p();
+
+    let mut suggestions: Vec<(&str, f64)> = dict
+        .iter()
+        .map(|&(word, frequency)| {
+            let similarity = (query - Hash::new(word)).normalized();
+            let popularity = if scale > 0.0 { (frequency as f64).ln_1p() / scale } else { 0.0 };
+            (word, similarity + FREQUENCY_WEIGHT * popularity)
+        })
+        .collect();
+    // The sort is stable, so ties keep the order of the dictionary.
+    suggestions.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(cmp::Ordering::Equal));
+    suggestions.truncate(max);
+
+    suggestions
+}
+
+/// The graduated distance of a query to the closest of some aliases.
+///
+/// The query is hashed once, and the scan stops at the first exact match. Without aliases, this
+/// gives `u32::MAX`.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::search::min_distance_to_aliases;
+///
+/// # #[cfg(feature = "weights-graduated")]
+/// assert_eq!(min_distance_to_aliases("jumbo", &["horse", "jumpo"]), 13);
+/// ```
+pub fn min_distance_to_aliases(query: &str, aliases: &[&str]) -> u32 {
+    let query = Hash::new(query);
+
+    let mut min = u32::MAX;
+    for alias in aliases {
+        min = min.min((query - Hash::new(alias)).dist());
+        if min == 0 {
+            break;
+        }
+    }
+
+    min
+}
+
+/// Classify a word into the bucket with the closest representative.
+///
+/// The buckets are pairs of a label and a representative hash (e.g. a
+/// [centroid](../struct.Hash.html#method.centroid) of the words of the bucket), and this gives
+/// the label of the bucket with the smallest graduated distance to the word. Ties favor the first
+/// of the buckets.
+///
+/// # Panics
+///
+/// This panics if there are no buckets.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::Hash;
+/// use eudex::search::classify_into;
+///
+/// let buckets = [("sales", Hash::new("sales")), ("marketing", Hash::new("marketing"))];
+/// assert_eq!(classify_into("sails", &buckets), "sales");
+/// ```
+pub fn classify_into<'a>(word: &str, buckets: &[(&'a str, Hash)]) -> &'a str {
+    let hash = Hash::new(word);
+
+    buckets
+        .iter()
+        .min_by_key(|&&(_, x)| (hash - x).dist())
+        .expect("no buckets to classify into")
+        .0
+}
+
+/// A matcher, which forces registered pairs of words apart.
+///
+/// Some pairs of words must always be told apart, regardless of how alike they sound (e.g.
+/// look-alike, sound-alike drug names). This matches words as
+/// [`Difference::similar`](../struct.Difference.html#method.similar) does, except for the
+/// registered pairs, which are never similar. The pairs are unordered and compared case
+/// insensitively.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::search::ExclusionMatcher;
+///
+/// let mut matcher = ExclusionMatcher::new();
+/// matcher.exclude("jumbo", "jumpo");
+///
+/// assert!(!matcher.similar("Jumpo", "jumbo"));
+/// assert!(matcher.similar("maier", "meyer"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ExclusionMatcher {
+    /// The excluded pairs, lowercased and ordered.
+    excluded: HashSet<(String, String)>,
+}
+
+impl ExclusionMatcher {
+    /// Create a matcher without excluded pairs.
+    #[inline]
+    pub fn new() -> ExclusionMatcher {
+        ExclusionMatcher::default()
+    }
+
+    /// Create a matcher excluding some pairs.
+    pub fn from_pairs(pairs: &[(&str, &str)]) -> ExclusionMatcher {
+        let mut matcher = ExclusionMatcher::new();
+        for &(a, b) in pairs {
+            matcher.exclude(a, b);
+        }
+
+        matcher
+    }
+
+    /// The key of a pair.
+    fn key(a: &str, b: &str) -> (String, String) {
+        let (a, b) = (a.to_lowercase(), b.to_lowercase());
+        if a <= b {
+            (a, b)
+        } else {
+            (b, a)
+        }
+    }
+
+    /// Force a pair of words apart.
+    pub fn exclude(&mut self, a: &str, b: &str) {
+        self.excluded.insert(ExclusionMatcher::key(a, b));
+    }
+
+    /// Is a pair of words forced apart?
+    pub fn is_excluded(&self, a: &str, b: &str) -> bool {
+        self.excluded.contains(&ExclusionMatcher::key(a, b))
+    }
+
+    /// Are two words similar, and not forced apart?
+    pub fn similar(&self, a: &str, b: &str) -> bool {
+        (Hash::new(a) - Hash::new(b)).similar() && !self.is_excluded(a, b)
+    }
+}
+
+/// A distance matrix, which computes its entries on demand.
+///
+/// As opposed to [`distance_matrix`](./fn.distance_matrix.html), which computes all the pairs up
+/// front, this hashes the words once and computes the graduated distance of a pair when it is
+/// first queried, caching it for later queries. This pays off when only some of the pairs are
+/// needed, e.g. in exploratory analysis. Since the distance is symmetric, `(i, j)` and `(j, i)`
+/// share an entry.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::search::LazyDistanceMatrix;
+///
+/// let mut matrix = LazyDistanceMatrix::new(&["jumbo", "horse", "jumpo"]);
+/// assert_eq!(matrix.get(0, 2), matrix.get(2, 0));
+/// # #[cfg(feature = "weights-graduated")]
+/// assert_eq!(matrix.get(0, 2), 13);
+/// assert_eq!(matrix.cached(), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct LazyDistanceMatrix {
+    /// The hashes of the words.
+    hashes: Vec<Hash>,
+    /// The computed distances, by their ordered pair of indices.
+    cache: HashMap<(usize, usize), u32>,
+}
+
+impl LazyDistanceMatrix {
+    /// Create a matrix of some words.
+    pub fn new(words: &[&str]) -> LazyDistanceMatrix {
+        LazyDistanceMatrix::from_hashes(words.iter().map(|x| Hash::new(x)).collect())
+    }
+
+    /// Create a matrix of some hashes.
+    pub fn from_hashes(hashes: Vec<Hash>) -> LazyDistanceMatrix {
+        LazyDistanceMatrix {
+            hashes,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Get the graduated distance between word `i` and word `j`, computing it if not cached.
+    ///
+    /// The diagonal is always 0, and is not cached.
+    ///
+    /// # Panics
+    ///
+    /// This panics if either index is out of bounds.
+    pub fn get(&mut self, i: usize, j: usize) -> u32 {
+        let (a, b) = (self.hashes[i], self.hashes[j]);
+        if i == j {
+            return 0;
+        }
+
+        *self
+            .cache
+            .entry((i.min(j), i.max(j)))
+            .or_insert_with(|| (a - b).dist())
+    }
+
+    /// The number of words.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Are there no words?
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// The number of cached distances.
+    #[inline]
+    pub fn cached(&self) -> usize {
+        self.cache.len()
+    }
+}
+
+/// A rule of which differences constitute similarity.
+///
+/// Domains define "similar" differently, so the matching functions taking a predicate (e.g.
+/// [`neighbors_by`](./fn.neighbors_by.html)) can be given any rule. Besides the built-in rules
+/// ([`MaxDist`](./struct.MaxDist.html), [`SameInitial`](./struct.SameInitial.html), and
+/// [`ClassAtMost`](./struct.ClassAtMost.html)), this is implemented for closures taking a
+/// difference.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::{Difference, Hash};
+/// use eudex::search::SimilarityPredicate;
+///
+/// let homophones = |x: Difference| x.dist_no_discriminant() == 0;
+/// assert!(homophones.is_similar(Hash::new("kat") - Hash::new("gat")));
+/// ```
+pub trait SimilarityPredicate {
+    /// Does this difference constitute similarity?
+    fn is_similar(&self, diff: Difference) -> bool;
+}
+
+impl<F: Fn(Difference) -> bool> SimilarityPredicate for F {
+    #[inline]
+    fn is_similar(&self, diff: Difference) -> bool {
+        self(diff)
+    }
+}
+
+/// Similarity within a graduated distance.
+///
+/// A difference is similar if its graduated distance is at most the given one, as in
+/// [`neighbors`](./fn.neighbors.html).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MaxDist(pub u32);
+
+impl SimilarityPredicate for MaxDist {
+    #[inline]
+    fn is_similar(&self, diff: Difference) -> bool {
+        diff.dist() <= self.0
+    }
+}
+
+/// Similarity starting with the same sound.
+///
+/// A difference is similar if
+/// [`Difference::similar_same_initial`](../struct.Difference.html#method.similar_same_initial) is
+/// true for the given threshold, i.e. the first phones agree and the graduated distance is below
+/// the threshold.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SameInitial(pub u32);
+
+impl SimilarityPredicate for SameInitial {
+    #[inline]
+    fn is_similar(&self, diff: Difference) -> bool {
+        diff.similar_same_initial(self.0)
+    }
+}
+
+/// Similarity by match category.
+///
+/// A difference is similar if its [category](../enum.Match.html) is at least as close as the
+/// given one, e.g. `ClassAtMost(Match::Homophone)` accepts exact matches and homophones.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ClassAtMost(pub Match);
+
+impl SimilarityPredicate for ClassAtMost {
+    #[inline]
+    fn is_similar(&self, diff: Difference) -> bool {
+        diff.classify() <= self.0
+    }
+}
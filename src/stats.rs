@@ -0,0 +1,377 @@
+//! Statistics of distances, for calibrating thresholds.
+
+use std::collections::HashSet;
+
+use {weights, Difference, Hash};
+
+/// The number of markers of a P² estimator.
+const MARKERS: usize = 5;
+
+/// Streaming estimation of quantiles of the graduated distance.
+///
+/// This takes constant memory, regardless of the number of distances pushed, using the P²
+/// algorithm (Jain and Chlamtac, 1985) for every tracked quantile: five markers are kept per
+/// quantile, and adjusted by piecewise-parabolic interpolation as the distances come in. The
+/// estimates are approximate, but generally close once a few hundred distances have been pushed,
+/// and exact until five have been.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::Hash;
+/// use eudex::stats::DistanceQuantiles;
+///
+/// let mut quantiles = DistanceQuantiles::new(&[0.5, 0.9]);
+/// for &(a, b) in &[("jumbo", "jumpo"), ("maier", "meyer"), ("jumbo", "horse")] {
+///     quantiles.push(Hash::new(a) - Hash::new(b));
+/// }
+///
+/// # #[cfg(feature = "weights-graduated")]
+/// assert_eq!(quantiles.estimates(), [Some(13.0), Some(41.0)]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct DistanceQuantiles {
+    /// The estimators of the quantiles, in order.
+    estimators: Vec<Estimator>,
+    /// The number of distances pushed.
+    len: usize,
+}
+
+impl DistanceQuantiles {
+    /// Track the given quantiles.
+    ///
+    /// # Panics
+    ///
+    /// This panics if any quantile is not strictly between 0 and 1.
+    pub fn new(quantiles: &[f64]) -> DistanceQuantiles {
+        DistanceQuantiles {
+            estimators: quantiles.iter().map(|&p| Estimator::new(p)).collect(),
+            len: 0,
+        }
+    }
+
+    /// Push the distance of a difference.
+    pub fn push(&mut self, difference: Difference) {
+        let dist = difference.dist() as f64;
+        for estimator in &mut self.estimators {
+            estimator.push(dist);
+        }
+        self.len += 1;
+    }
+
+    /// The estimates of the quantiles, in the order they were given.
+    ///
+    /// These are `None` if no distances were pushed.
+    pub fn estimates(&self) -> Vec<Option<f64>> {
+        self.estimators.iter().map(|x| x.estimate()).collect()
+    }
+
+    /// The number of distances pushed.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Were no distances pushed?
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// How confusable a word is with the words of a dictionary.
+///
+/// See [`confusability`](./fn.confusability.html).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ConfusabilityReport {
+    /// The number of words, which are [similar](../struct.Difference.html#method.similar).
+    pub similar: usize,
+    /// The graduated distance of the nearest word, if any.
+    pub nearest: Option<u32>,
+    /// The mean graduated distance, or 0 if the dictionary is empty.
+    pub mean: f64,
+}
+
+/// Measure how confusable a word is with the words of a dictionary.
+///
+/// This is meant for checks of how many things sound like a word (e.g. drug names or
+/// usernames). Words of the dictionary, which equal the word, count as well.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::Hash;
+/// use eudex::stats::confusability;
+///
+/// let dict = [Hash::new("jumpo"), Hash::new("horse")];
+/// let report = confusability("jumbo", &dict);
+/// # #[cfg(feature = "weights-graduated")]
+/// assert_eq!(report.similar, 1);
+/// # #[cfg(feature = "weights-graduated")]
+/// assert_eq!(report.nearest, Some(13));
+/// ```
+pub fn confusability(word: &str, dict: &[Hash]) -> ConfusabilityReport {
+    let hash = Hash::new(word);
+
+    let mut report = ConfusabilityReport {
+        similar: 0,
+        nearest: None,
+        mean: 0.0,
+    };
+    let mut sum = 0;
+    for &x in dict {
+        let diff = hash - x;
+        let dist = diff.dist();
+        if diff.similar() {
+            report.similar += 1;
+        }
+        report.nearest = Some(report.nearest.map_or(dist, |nearest| nearest.min(dist)));
+        sum += dist as u64;
+    }
+    if !dict.is_empty() {
+        report.mean = sum as f64 / dict.len() as f64;
+    }
+
+    report
+}
+
+/// The phonetic diversity of a corpus.
+///
+/// This is the number of distinct hashes of the words, divided by the number of words, which
+/// tells how much a vocabulary is collapsed by the hashing: it is 1 if no two words share a
+/// hash, and approaches 0 as more words are homophones (or repeated). An empty corpus has a
+/// diversity of 1.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::stats::corpus_diversity;
+///
+/// assert_eq!(corpus_diversity(&["jumbo", "horse"]), 1.0);
+/// assert_eq!(corpus_diversity(&["maier", "meyer", "mayer", "horse"]), 0.5);
+/// ```
+pub fn corpus_diversity(words: &[&str]) -> f64 {
+    if words.is_empty() {
+        return 1.0;
+    }
+
+    let distinct: HashSet<Hash> = words.iter().map(|x| Hash::new(x)).collect();
+    distinct.len() as f64 / words.len() as f64
+}
+
+/// Measure how much substituting a single character changes the hash of a word.
+///
+/// This is the graduated distance between the hash of the word and the hash of the word, in
+/// which the character at `pos` (counting characters, not bytes) is replaced by `new_char`. It
+/// quantifies how phonetically sensitive a position is, e.g. for generating realistic typos or
+/// highlighting risky edits.
+///
+/// # Panics
+///
+/// This panics if `pos` is out of bounds.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::stats::edit_impact;
+///
+/// assert_eq!(edit_impact("jumbo", 4, 'a'), 0);
+/// # #[cfg(feature = "weights-graduated")]
+/// assert_eq!(edit_impact("jumbo", 3, 'p'), 13);
+/// ```
+pub fn edit_impact(word: &str, pos: usize, new_char: char) -> u32 {
+    let (start, old_char) = word.char_indices().nth(pos).expect("position out of bounds");
+
+    let mut edited = String::with_capacity(word.len() + new_char.len_utf8());
+    edited.push_str(&word[..start]);
+    edited.push(new_char);
+    edited.push_str(&word[start + old_char.len_utf8()..]);
+
+    (Hash::new(word) - Hash::new(&edited)).dist()
+}
+
+/// The weights of the 64 bits of a difference under `Difference::dist`, in ascending order.
+fn bit_weights() -> Vec<u32> {
+    let mut res: Vec<u32> = weights::DEFAULT.iter().flat_map(|&x| vec![x; 8]).collect();
+    res.sort_unstable();
+
+    res
+}
+
+/// The largest number of differing bits, which can still be below a threshold.
+///
+/// This is the Hamming distance of the most permissive difference, whose graduated distance is
+/// below `threshold` (as in [`similar`](../struct.Difference.html#method.similar), which uses a
+/// threshold of 15): the differing bits are all in the last phones, which weigh the least. If no
+/// difference is below the threshold (i.e. it is 0), this is 0 as well.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::stats::coverage_radius;
+///
+/// // Eight bits of the last phone and three of the one before it.
+/// # #[cfg(feature = "weights-graduated")]
+/// assert_eq!(coverage_radius(15), 11);
+/// ```
+pub fn coverage_radius(threshold: u32) -> u32 {
+    let mut dist = 0;
+    let mut res = 0;
+    for weight in bit_weights() {
+        dist += weight;
+        if dist >= threshold {
+            break;
+        }
+        res += 1;
+    }
+
+    res
+}
+
+/// The smallest number of differing bits, which can reach a threshold.
+///
+/// This is the Hamming distance of the least permissive difference, whose graduated distance is
+/// not below `threshold`: the differing bits are all in the first letter, which weighs the most.
+/// It is `None` if the threshold is above the largest possible distance.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::stats::min_bits_to_exceed;
+///
+/// // A single bit of the first letter.
+/// # #[cfg(feature = "weights-graduated")]
+/// assert_eq!(min_bits_to_exceed(15), Some(1));
+/// # #[cfg(feature = "weights-graduated")]
+/// assert_eq!(min_bits_to_exceed(1000), None);
+/// ```
+pub fn min_bits_to_exceed(threshold: u32) -> Option<u32> {
+    let mut dist = 0;
+    let mut res = 0;
+    for weight in bit_weights().into_iter().rev() {
+        if dist >= threshold {
+            return Some(res);
+        }
+        dist += weight;
+        res += 1;
+    }
+
+    if dist >= threshold {
+        Some(res)
+    } else {
+        None
+    }
+}
+
+/// A P² estimator of a single quantile.
+#[derive(Clone, Debug)]
+struct Estimator {
+    /// The quantile.
+    p: f64,
+    /// The number of values pushed.
+    count: usize,
+    /// The heights of the markers.
+    ///
+    /// Until all markers are placed, these are the (sorted) values pushed so far.
+    heights: [f64; MARKERS],
+    /// The actual positions of the markers (counting from 1).
+    positions: [f64; MARKERS],
+    /// The desired positions of the markers.
+    desired: [f64; MARKERS],
+    /// The increments of the desired positions.
+    increments: [f64; MARKERS],
+}
+
+impl Estimator {
+    /// Estimate the quantile `p`.
+    fn new(p: f64) -> Estimator {
+        assert!(p > 0.0 && p < 1.0, "the quantile must be between 0 and 1");
+
+        Estimator {
+            p,
+            count: 0,
+            heights: [0.0; MARKERS],
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired: [1.0, 1.0 + 2.0 * p, 1.0 + 4.0 * p, 3.0 + 2.0 * p, 5.0],
+            increments: [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0],
+        }
+    }
+
+    /// Push a value.
+    fn push(&mut self, x: f64) {
+        if self.count < MARKERS {
+            // Place the markers at the first values, keeping them sorted.
+            let mut i = self.count;
+            while i > 0 && self.heights[i - 1] > x {
+                self.heights[i] = self.heights[i - 1];
+                i -= 1;
+            }
+            self.heights[i] = x;
+            self.count += 1;
+            return;
+        }
+        self.count += 1;
+
+        // Find the cell of the value, extending the extreme markers if needed.
+        let cell = if x < self.heights[0] {
+            self.heights[0] = x;
+            0
+        } else if x >= self.heights[MARKERS - 1] {
+            self.heights[MARKERS - 1] = x;
+            MARKERS - 2
+        } else {
+            (1..MARKERS).find(|&i| x < self.heights[i]).unwrap() - 1
+        };
+
+        for position in &mut self.positions[cell + 1..] {
+            *position += 1.0;
+        }
+        for (desired, increment) in self.desired.iter_mut().zip(&self.increments) {
+            *desired += increment;
+        }
+
+        // Move the middle markers towards their desired positions.
+        for i in 1..MARKERS - 1 {
+            let offset = self.desired[i] - self.positions[i];
+            if (offset >= 1.0 && self.positions[i + 1] - self.positions[i] > 1.0)
+                || (offset <= -1.0 && self.positions[i - 1] - self.positions[i] < -1.0)
+            {
+                let d = offset.signum();
+                let parabolic = self.parabolic(i, d);
+                // Fall back to linear interpolation, if the markers would get out of order.
+                let ordered = self.heights[i - 1] < parabolic && parabolic < self.heights[i + 1];
+                self.heights[i] = if ordered { parabolic } else { self.linear(i, d) };
+                self.positions[i] += d;
+            }
+        }
+    }
+
+    /// The piecewise-parabolic prediction of the height of marker `i`, moved by `d`.
+    fn parabolic(&self, i: usize, d: f64) -> f64 {
+        let (q, n) = (&self.heights, &self.positions);
+
+        q[i] + d / (n[i + 1] - n[i - 1])
+            * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]))
+    }
+
+    /// The linear prediction of the height of marker `i`, moved by `d`.
+    fn linear(&self, i: usize, d: f64) -> f64 {
+        let (q, n) = (&self.heights, &self.positions);
+        let j = if d < 0.0 { i - 1 } else { i + 1 };
+
+        q[i] + d * (q[j] - q[i]) / (n[j] - n[i])
+    }
+
+    /// The estimate of the quantile.
+    ///
+    /// Until more values than markers are pushed, this is the exact quantile (by nearest rank).
+    fn estimate(&self) -> Option<f64> {
+        match self.count {
+            0 => None,
+            count if count <= MARKERS => {
+                let rank = (self.p * count as f64).ceil() as usize;
+                Some(self.heights[rank.max(1) - 1])
+            }
+            _ => Some(self.heights[2]),
+        }
+    }
+}
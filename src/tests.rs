@@ -1,4 +1,5 @@
 use super::*;
+#[cfg(feature = "nightly")]
 use test::Bencher;
 
 #[test]
@@ -31,6 +32,7 @@ fn test_mismatch() {
 }
 
 #[test]
+#[cfg(feature = "weights-graduated")]
 fn test_distance() {
     assert!((Hash::new("lizzard") - Hash::new("wizzard")).dist() > (Hash::new("rick") - Hash::new("rolled")).dist());
     assert!((Hash::new("bannana") - Hash::new("panana")).dist() >= (Hash::new("apple") - Hash::new("abple")).dist());
@@ -47,6 +49,7 @@ fn test_reflexivity() {
 }
 
 #[test]
+#[cfg(feature = "weights-graduated")]
 fn test_similar() {
     // Similar.
     assert!((Hash::new("yay") - Hash::new("yuy")).similar());
@@ -75,6 +78,2419 @@ fn test_similar() {
     assert!(!(Hash::new("nice") - Hash::new("mice")).similar());
 }
 
+#[test]
+fn test_config_default() {
+    let config = HashConfig::new();
+    for &word in &["", "a", "computer", "riiiindom", "tuuli", "schmidt", "Straße"] {
+        assert_eq!(config.build_hash(word), Hash::new(word));
+    }
+}
+
+#[test]
+fn test_vowel_length() {
+    let config = HashConfig::new().vowel_length(true);
+
+    // Phonemic vowel length (Finnish).
+    assert!(config.build_hash("tuli") != config.build_hash("tuuli"));
+    assert!(config.build_hash("tuli") != config.build_hash("tuuuli"));
+    assert!(config.build_hash("muta") != config.build_hash("muuta"));
+    assert!(config.build_hash("tika") != config.build_hash("tikaa"));
+    assert!(config.build_hash("sika") != config.build_hash("siika"));
+    assert_eq!(Hash::new("tuli"), Hash::new("tuuli"));
+
+    // Marked vowels do not collide with consonants.
+    assert!(config.build_hash("ttaa") != config.build_hash("ttm"));
+    for &x in &["tttm", "ttma", "ttmm", "ttml", "ttum", "ttim", "ttrm"] {
+        assert!(config.build_hash("ttaa") != config.build_hash(x));
+    }
+    // Nor with the confident labials of custom tables, which have both of the marked bits.
+    let tables = raw::TableBuilder::new().confident("bcdfghjklmnpqrstvwxz").build();
+    assert_eq!(tables.phone(b'b'), Some(0b11001000));
+    assert!((0..=255).filter_map(|x| tables.phone(x)).all(|x| x & !1 != raw::VOWEL_LENGTH));
+    let confident = HashConfig::new().vowel_length(true).tables(tables);
+    for &x in &["ttb", "ttf", "ttp", "ttv", "ttm"] {
+        assert!(confident.build_hash("ttaa") != confident.build_hash(x), "{}", x);
+    }
+    for &x in &["tuuli", "tikaa", "siika", "muuta"] {
+        assert!(config.build_hash(x).unpack()[1..].iter().any(|&x| x & raw::VOWEL_LENGTH != 0));
+    }
+    // Vowels dropped as duplicates, and the first letter, are not marked, rather than marking
+    // the previous phone or adding a phone.
+    assert_eq!(config.build_hash("kruu"), config.build_hash("kru"));
+    assert_eq!(config.build_hash("taali"), config.build_hash("tali"));
+    assert_eq!(config.build_hash("taali").unpack(), Hash::new("tali").unpack());
+    assert_eq!(config.build_hash("aamu"), config.build_hash("amu"));
+
+    // Long vowels are still close to their short counterparts.
+    assert!((config.build_hash("tuli") - config.build_hash("tuuli")).similar());
+    // Runs of different vowels are not lengthened.
+    assert_eq!(config.build_hash("riyiyindom"), config.build_hash("ryiyiyndom"));
+    assert_eq!(config.build_hash("tuuli"), config.build_hash("tuuuuli"));
+    assert_eq!(config.build_hash("Tuuli"), config.build_hash("tuUli"));
+}
+
+#[test]
+fn test_vowel_runs() {
+    let config = HashConfig::new().vowel_runs(true);
+
+    // Three vowel lengths (Estonian, and the Finnish examples).
+    let tuli = config.build_hash("tuli");
+    let tuuli = config.build_hash("tuuli");
+    let tuuuli = config.build_hash("tuuuli");
+    assert!(tuli != tuuli);
+    assert!(tuuli != tuuuli);
+    assert!(tuli != tuuuli);
+    assert!(config.build_hash("tika") != config.build_hash("tikaa"));
+    assert!(config.build_hash("tikaa") != config.build_hash("tikaaa"));
+
+    // Short runs agree with `vowel_length`.
+    let length = HashConfig::new().vowel_length(true);
+    assert_eq!(tuli, length.build_hash("tuli"));
+    assert_eq!(tuuli, length.build_hash("tuuli"));
+    assert_eq!(length.build_hash("tuuli"), length.build_hash("tuuuli"));
+
+    // The longest bucket is capped.
+    assert_eq!(tuuuli, config.build_hash("tuuuuli"));
+    assert_eq!(tuuuli, config.build_hash("tuuuuuuli"));
+    assert!((tuli - tuuuli).similar());
+    assert_eq!(config.build_hash("riyiyindom"), config.build_hash("ryiyiyndom"));
+
+    // The marks do not collide with any phone (the fricative bit, in particular).
+    for x in 0..=255 {
+        if let Some(phone) = raw::phone(x) {
+            assert!(phone & raw::VOWEL_LENGTH != raw::VOWEL_LENGTH);
+        }
+    }
+    for x in "ɑæɐəɛɪiʊuɔoʌyøœɒbdfɡhjklmnŋprɹsʃtθðvwzʒʧʤ".chars() {
+        if let Some(phone) = raw::ipa_phone(x) {
+            assert!(phone & raw::VOWEL_LENGTH != raw::VOWEL_LENGTH);
+        }
+    }
+    let ttaaa = config.build_hash("ttaaa");
+    for &x in &["tts", "ttsa", "ttas", "ttaa", "ttf", "ttz", "tth"] {
+        assert!(ttaaa != config.build_hash(x));
+    }
+    // The confident labials of custom tables share the top bits of the marks, but are distinct.
+    let tables = raw::TableBuilder::new().confident("bcdfghjklmnpqrstvwxz").build();
+    assert!((0..=255).filter_map(|x| tables.phone(x)).all(|x| x & !1 != raw::VOWEL_OVERLONG));
+    let confident = HashConfig::new().vowel_runs(true).tables(tables);
+    for &x in &["ttb", "ttf", "ttp", "ttv", "ttaa", "ttba"] {
+        assert!(confident.build_hash("ttaaa") != confident.build_hash(x), "{}", x);
+    }
+    assert!(config.build_hash("tikaaa") != config.build_hash("tikas"));
+}
+
+#[test]
+fn test_dist_soft_initial() {
+    // Vowel initials.
+    for &(a, b) in &[("iesus", "aesus"), ("ebert", "abert"), ("umlaut", "omlaut"), ("oscar", "escar")] {
+        let diff = Hash::new(a) - Hash::new(b);
+        assert!(diff.dist_soft_initial() <= diff.dist());
+        if weights::DEFAULT[7] > 2 {
+            assert!(diff.dist_soft_initial() < diff.dist());
+        }
+        #[cfg(feature = "weights-graduated")]
+        assert!(diff.dist_soft_initial() < 15);
+    }
+
+    // Consonant initials.
+    for &(a, b) in &[("horse", "norse"), ("nice", "mice"), ("bannana", "panana"), ("jesus", "iesus")] {
+        let diff = Hash::new(a) - Hash::new(b);
+        assert_eq!(diff.dist_soft_initial(), diff.dist());
+    }
+    // Long words, whose first trailing phone sets the vowel bit of the first byte.
+    let diff = Hash::new("crnjolztuhlkbkm") - Hash::new("krywrzjdhdyipxs");
+    assert!(diff.xor() >> 56 != 0);
+    assert_eq!(diff.dist_soft_initial(), diff.dist());
+
+    assert_eq!((Hash::new("aesus") - Hash::new("aesus")).dist_soft_initial(), 0);
+
+    // Softening never increases the distance.
+    let words = random_words(200);
+    for pair in words.windows(2) {
+        let diff = Hash::new(&pair[0]) - Hash::new(&pair[1]);
+        assert!(diff.dist_soft_initial() <= diff.dist());
+    }
+}
+
+#[test]
+#[cfg(feature = "weights-graduated")]
+fn test_index() {
+    use index::PhoneticIndex;
+
+    let mut index = PhoneticIndex::from_words(&["jumbo", "horse", "maier", "computer"]);
+    assert_eq!(index.len(), 4);
+    assert_eq!(index.lookup("jumpo", 15), ["jumbo"]);
+
+    // Insertion.
+    index.insert("meyer");
+    index.insert("meyer");
+    assert_eq!(index.len(), 5);
+    assert_eq!(index.lookup("meier", 10), ["maier", "meyer"]);
+    index.insert("comp-uter");
+    assert_eq!(index.get("computer"), ["computer", "comp-uter"]);
+
+    // Removal.
+    assert!(index.remove("maier"));
+    assert!(!index.remove("maier"));
+    assert!(!index.remove("norse"));
+    assert_eq!(index.lookup("meier", 10), ["meyer"]);
+    assert!(index.remove("horse"));
+    assert!(index.lookup("horse", 0).is_empty());
+    assert!(index.get("horse").is_empty());
+    assert_eq!(index.len(), 4);
+
+    // Re-insertion.
+    index.insert("horse");
+    assert_eq!(index.lookup("horse", 0), ["horse"]);
+
+    for word in &["jumbo", "meyer", "computer", "comp-uter", "horse"] {
+        assert!(index.remove(word));
+    }
+    assert!(index.is_empty());
+}
+
+#[test]
+fn test_phone_jaccard() {
+    assert_eq!(raw::phone_jaccard("computer", "computer"), 1.0);
+    assert_eq!(raw::phone_jaccard("Computer", "c-o-m-p-u-t-e-r"), 1.0);
+    assert_eq!(raw::phone_jaccard("", ""), 1.0);
+    assert_eq!(raw::phone_jaccard("map", "kit"), 0.0);
+    assert_eq!(raw::phone_jaccard("mom", "kid"), 0.0);
+    assert_eq!(raw::phone_jaccard("", "kid"), 0.0);
+
+    let partial = raw::phone_jaccard("jumbo", "jumpo");
+    assert!(partial > 0.0 && partial < 1.0);
+    assert_eq!(partial, raw::phone_jaccard("jumpo", "jumbo"));
+}
+
+#[test]
+fn test_confident() {
+    use raw::TableBuilder;
+
+    // The default set.
+    let config = HashConfig::new().tables(TableBuilder::new().confident("LRXZQ").build());
+    for &word in &["lizzard", "wizzard", "quixotic", "rolled", "kat", "computer"] {
+        assert_eq!(config.build_hash(word), Hash::new(word));
+    }
+
+    // Adding a letter.
+    let config = HashConfig::new().tables(TableBuilder::new().confident("lrxzqk").build());
+    assert!((config.build_hash("kat") - config.build_hash("gat")).dist() > (Hash::new("kat") - Hash::new("gat")).dist());
+    assert!((config.build_hash("bak") - config.build_hash("bag")).dist() > (Hash::new("bak") - Hash::new("bag")).dist());
+
+    // Removing a letter.
+    let config = HashConfig::new().tables(TableBuilder::new().confident("rxzq").build());
+    assert!((config.build_hash("lol") - config.build_hash("wow")).dist() < (Hash::new("lol") - Hash::new("wow")).dist());
+
+    // Vowels are unaffected.
+    let config = HashConfig::new().tables(TableBuilder::new().confident("lrxzqaeiouy").build());
+    assert_eq!(config.build_hash("aeiou"), Hash::new("aeiou"));
+}
+
+#[test]
+fn test_builder() {
+    for &word in &["", "a", "computer", "riiiindom", "Straße", "supercalifragilisticexpialidocious"] {
+        let mut builder = HashBuilder::new();
+        builder.write(word.as_bytes());
+        assert_eq!(builder.finish(), Hash::new(word));
+    }
+
+    assert_eq!(HashBuilder::new().finish(), Hash::new(""));
+    let mut builder = HashBuilder::default();
+    builder.write(b"computer");
+    assert_eq!(builder.finish(), Hash::new("computer"));
+}
+
+#[test]
+fn test_writer() {
+    use std::io::{self, Write};
+
+    for &word in &["computer", "Straße", "supercalifragilisticexpialidocious"] {
+        // Every split, including ones inside of characters.
+        for i in 0..word.len() + 1 {
+            let mut writer = HashWriter::new();
+            writer.write_all(&word.as_bytes()[..i]).unwrap();
+            writer.write_all(&word.as_bytes()[i..]).unwrap();
+            writer.flush().unwrap();
+            assert_eq!(writer.finish(), Hash::new(word));
+        }
+
+        // Single byte chunks.
+        let mut writer = HashWriter::new();
+        for x in word.bytes() {
+            writer.write_all(&[x]).unwrap();
+        }
+        assert_eq!(writer.finish(), Hash::new(word));
+
+        let mut writer = HashWriter::new();
+        io::copy(&mut word.as_bytes(), &mut writer).unwrap();
+        assert_eq!(writer.finish(), Hash::new(word));
+    }
+}
+
+#[test]
+fn test_find_collisions() {
+    use search::find_collisions;
+
+    let words = [
+        "celebrex", "celexa", "cerebyx", "zantac", "xanax", "zyrtec", "zyprexa", "klonopin",
+        "clonidine", "jesus", "jeuses", "maier", "meyer", "möier", "jumpo", "jumbo", "horse",
+        "norse", "goth", "god",
+    ];
+
+    for &threshold in &[0, 5, 15, 35, 70, 200] {
+        let collisions = find_collisions(&words, threshold);
+
+        // Compare to the full O(n²) scan.
+        let mut expected = Vec::new();
+        for i in 0..words.len() {
+            for j in i + 1..words.len() {
+                let dist = (Hash::new(words[i]) - Hash::new(words[j])).dist();
+                if dist <= threshold {
+                    expected.push((i, j, dist));
+                }
+            }
+        }
+        assert_eq!(collisions, expected);
+    }
+
+    #[cfg(feature = "weights-graduated")]
+    {
+        let collisions = find_collisions(&words, 15);
+        for &(i, j) in &[(9, 10), (11, 12), (11, 13), (12, 13), (14, 15), (18, 19)] {
+            assert_eq!(collisions.iter().filter(|x| (x.0, x.1) == (i, j)).count(), 1);
+        }
+        assert!(!collisions.iter().any(|x| (x.0, x.1) == (16, 17)));
+    }
+
+    assert!(find_collisions(&[], 15).is_empty());
+    assert!(find_collisions(&["horse"], 15).is_empty());
+}
+
+#[test]
+fn test_stable_fingerprint() {
+    // These values are promised to be stable. Do not change them.
+    let golden = [
+        ("", 0xf800),
+        ("a", 0x0000),
+        ("Smith", 0x9360),
+        ("Schmidt", 0x95b0),
+        ("computer", 0x1300),
+        ("supercalifragilistic", 0x95dc),
+        ("Müller", 0x6000),
+        ("4chan", 0xfda0),
+        ("nmnmnm", 0x6a00),
+        ("mississippi", 0x6700),
+        ("thompson", 0x9eda),
+        ("jesus", 0x4e00),
+        ("hello", 0x3800),
+        ("xylophone", 0xbda0),
+    ];
+
+    for &(word, fingerprint) in &golden {
+        assert_eq!(Hash::stable_fingerprint(word), fingerprint, "{}", word);
+    }
+
+    assert_eq!(Hash::stable_fingerprint("Smith"), Hash::stable_fingerprint("smyth"));
+}
+
+#[test]
+fn test_ocr() {
+    let pairs = [
+        ("rnodern", "modern"),
+        ("RNodern", "modern"),
+        ("holcl", "hold"),
+        ("clog", "dog"),
+        ("vvater", "water"),
+        ("c0mputer", "computer"),
+        ("1ove", "love"),
+    ];
+    for &(ocr, word) in &pairs {
+        assert_eq!(Hash::new_ocr(ocr), Hash::new_ocr(word));
+    }
+
+    for &(ocr, word) in &[("rnodern", "modern"), ("clog", "dog"), ("vvater", "water"), ("1ove", "love")] {
+        assert!(Hash::new(ocr) != Hash::new(word));
+    }
+
+    assert_eq!(Hash::new_ocr("vvater"), Hash::new("water"));
+    assert_eq!(Hash::new_ocr("Müller"), Hash::new("Müller"));
+    assert_eq!(Hash::new_ocr(""), Hash::new(""));
+}
+
+#[test]
+fn test_phrase_distance() {
+    use phrase::phrase_distance;
+
+    // Reordering.
+    assert_eq!(phrase_distance("John Smith", "Smith John"), 0);
+    assert_eq!(phrase_distance("the quick brown fox", "fox brown quick the"), 0);
+    assert_eq!(phrase_distance("John  Smith!", "smith, JOHN"), 0);
+    assert_eq!(phrase_distance("", ""), 0);
+    assert_eq!(
+        phrase_distance("Jon Smyth", "Smith John"),
+        (Hash::new("Jon") - Hash::new("John")).dist() + (Hash::new("Smyth") - Hash::new("Smith")).dist()
+    );
+
+    // Partial matches.
+    let missing = (Hash::new("Smith") - Hash::new("")).dist();
+    assert_eq!(phrase_distance("John Smith", "John"), missing);
+    assert_eq!(phrase_distance("John", "John Smith"), missing);
+    assert!(phrase_distance("John Smith", "Jon Smyth") < phrase_distance("John Smith", "Jane Doe"));
+    assert!(phrase_distance("John Smith", "Smith") < phrase_distance("John Smith", "Doe"));
+
+    // Equal words are assigned to each other.
+    let a = "horse norse";
+    let b = "norse gorse";
+    let optimal = (Hash::new("horse") - Hash::new("gorse")).dist();
+    assert_eq!(phrase_distance(a, b), optimal);
+
+    // Long phrases.
+    let long = "one two three four five six seven eight nine ten eleven twelve";
+    let reordered = "twelve eleven ten nine eight seven six five four three two one";
+    assert_eq!(phrase_distance(long, reordered), 0);
+    assert_eq!(phrase_distance(long, long), 0);
+    assert!(phrase_distance(long, "one two three four five six seven eight nine ten eleven twelve thirteen") > 0);
+}
+
+#[test]
+fn test_tables_consistent() {
+    assert!(raw::tables_consistent(26, 33, 26, 33));
+
+    // A letter too many or too few.
+    assert!(!raw::tables_consistent(27, 33, 26, 33));
+    assert!(!raw::tables_consistent(26, 32, 26, 33));
+    assert!(!raw::tables_consistent(26, 33, 25, 33));
+    assert!(!raw::tables_consistent(26, 33, 26, 34));
+}
+
+#[test]
+#[cfg(feature = "weights-graduated")]
+fn test_deduper() {
+    use index::Deduper;
+
+    let stream = [
+        "jumbo", "horse", "jumpo", "maier", "meyer", "norse", "Jumbo", "möier", "computer",
+        "comp-uter", "horse",
+    ];
+    let mut deduper = Deduper::new(15);
+    let decisions: Vec<Option<String>> = stream.iter().map(|x| deduper.see(x).map(|x| x.to_owned())).collect();
+
+    assert_eq!(decisions, [
+        None,
+        None,
+        Some("jumbo".to_owned()),
+        None,
+        Some("maier".to_owned()),
+        None,
+        Some("jumbo".to_owned()),
+        Some("maier".to_owned()),
+        None,
+        Some("computer".to_owned()),
+        Some("horse".to_owned()),
+    ]);
+    assert_eq!(deduper.words(), ["jumbo", "horse", "maier", "norse", "computer"]);
+
+    // Only exact duplicates.
+    let mut deduper = Deduper::new(0);
+    assert_eq!(deduper.see("jumbo"), None);
+    assert_eq!(deduper.see("jumpo"), None);
+    assert_eq!(deduper.see("JUMPO"), Some("jumpo"));
+}
+
+#[test]
+fn test_hashability() {
+    assert!(raw::hashability("rhythm") >= 0.8);
+    assert_eq!(raw::hashability("computer"), 1.0);
+    assert_eq!(raw::hashability("aeiou"), 0.0);
+    assert_eq!(raw::hashability("a"), 0.0);
+    assert_eq!(raw::hashability(""), 0.0);
+    assert_eq!(raw::hashability("!?!"), 0.0);
+    assert!(raw::hashability("ok") < 0.5);
+    assert_eq!(raw::hashability("pppppp"), raw::hashability("p"));
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_mmap_dict() {
+    use std::{env, fs};
+    use index::{MmapDict, PhoneticIndex};
+
+    let words = [
+        "jumbo", "horse", "jumpo", "maier", "meyer", "norse", "möier", "computer", "comp-uter",
+        "celebrex", "celexa", "cerebyx", "zantac", "xanax", "zyrtec", "zyprexa", "", "a", "ab",
+    ];
+    let path = env::temp_dir().join(format!("eudex-test-{}.dict", std::process::id()));
+    MmapDict::write(fs::File::create(&path).unwrap(), &words).unwrap();
+
+    let dict = MmapDict::open(&path).unwrap();
+    let index = PhoneticIndex::from_words(&words);
+    for &query in words.iter().chain(&["jambo", "nurse", "mayer", "zzz"]) {
+        for &max_dist in &[0, 15, 40, 100, 1000] {
+            // Compare to a full scan.
+            assert_eq!(dict.lookup(query, max_dist), index.lookup(query, max_dist));
+        }
+    }
+
+    assert_eq!(dict.lookup("jumpo", 15), ["jumpo", "jumbo"]);
+
+    MmapDict::write(fs::File::create(&path).unwrap(), &[]).unwrap();
+    assert!(MmapDict::open(&path).unwrap().lookup("jumpo", 1000).is_empty());
+
+    // Words must fit on a line.
+    let mut buf = Vec::new();
+    let err = MmapDict::write(&mut buf, &["jumbo", "jum\nbo"]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(buf.is_empty());
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_top_diff_byte() {
+    assert_eq!((Hash::new("horse") - Hash::new("norse")).top_diff_byte(), Some(7));
+    assert_eq!((Hash::new("jumpo") - Hash::new("jumbo")).top_diff_byte(), Some(3));
+    assert_eq!((Hash::new("java") - Hash::new("jAva")).top_diff_byte(), None);
+    assert_eq!((Hash::new("") - Hash::new("")).top_diff_byte(), None);
+}
+
+#[test]
+fn test_rank_words() {
+    use search::{rank, rank_words};
+
+    let words = [
+        "lizzard", "wizzard", "rick", "rolled", "bannana", "panana", "apple", "abple", "trump",
+        "drumpf", "gangam", "style", "youtube", "facebook", "jumbo", "jumpo", "maier", "meyer",
+    ];
+
+    let ranked = rank_words("meier", &words);
+    assert_eq!(ranked.len(), words.len());
+    assert_eq!(&ranked[..2], &[(16, 0), (17, 0)]);
+    assert!(ranked.windows(2).all(|x| x[0].1 <= x[1].1));
+    for &(i, dist) in &ranked {
+        assert_eq!(dist, (Hash::new("meier") - Hash::new(words[i])).dist());
+    }
+
+    let hashes: Vec<Hash> = words.iter().map(|x| Hash::new(x)).collect();
+    assert_eq!(rank(Hash::new("trump"), &hashes), rank_words("trump", &words));
+    assert_eq!(rank_words("trump", &words)[0], (8, 0));
+    assert!(rank_words("trump", &[]).is_empty());
+}
+
+#[test]
+fn test_similar_same_initial() {
+    // Different initials.
+    let dist = (Hash::new("bannana") - Hash::new("panana")).dist();
+    assert!(!(Hash::new("bannana") - Hash::new("panana")).similar_same_initial(dist + 1));
+    assert!(!(Hash::new("catherine") - Hash::new("katherine")).similar_same_initial(100));
+
+    // Same initials.
+    let dist = (Hash::new("jumpo") - Hash::new("jumbo")).dist();
+    assert!((Hash::new("jumpo") - Hash::new("jumbo")).similar_same_initial(dist + 1));
+    assert!((Hash::new("smith") - Hash::new("smyth")).similar_same_initial(1));
+    assert!(!(Hash::new("jumpo") - Hash::new("jumbo")).similar_same_initial(dist));
+}
+
+#[test]
+fn test_central_european() {
+    let pairs = [
+        // Hungarian.
+        ("Kovács", "Kovach"),
+        ("Szabó", "Sabo"),
+        ("Zsófia", "Zofia"),
+        ("Szőke", "Soke"),
+        // Polish.
+        ("Szymański", "Shymanski"),
+        ("Kowalczyk", "Kovalczyk"),
+        ("Chmielewski", "Hmielewski"),
+        ("Rzepka", "Zhepka"),
+        ("Wałęsa", "Valesa"),
+    ];
+
+    for &(native, romanized) in &pairs {
+        assert_eq!(
+            Hash::new_lang(native, Lang::CentralEuropean),
+            Hash::new_lang(romanized, Lang::CentralEuropean),
+            "{} {}",
+            native,
+            romanized,
+        );
+    }
+
+    assert!(Hash::new("Kovács") != Hash::new("Kovach"));
+    assert!(Hash::new_lang("Szabó", Lang::CentralEuropean) != Hash::new_lang("Kovach", Lang::CentralEuropean));
+    // Polish and Czech ch is [x], not [t͡ʃ].
+    assert_eq!(Lang::CentralEuropean.preprocess("chleb"), "hleb");
+    assert_eq!(Lang::CentralEuropean.preprocess("Wojciech"), "vojcieh");
+    assert!(Hash::new_lang("Chmielewski", Lang::CentralEuropean) != Hash::new_lang("Cmielewski", Lang::CentralEuropean));
+    assert_eq!(Lang::CentralEuropean.preprocess("Kovach"), "kovac");
+    assert_eq!(Lang::CentralEuropean.preprocess("Czajkowski"), "cajkovski");
+}
+
+#[test]
+fn test_fold_initial_vowel() {
+    // Vowel initials share a bucket.
+    let folded = Hash::new("aesus").fold_initial_vowel();
+    for &word in &["iesus", "esus", "oesus", "uesus", "yesus", "Aesus"] {
+        assert!(Hash::new(word) != Hash::new("aesus") || word == "Aesus");
+        assert_eq!(Hash::new(word).fold_initial_vowel(), folded);
+    }
+    assert!(Hash::new("aesop").fold_initial_vowel() != folded);
+
+    // Consonant initials are unchanged.
+    for &word in &["jesus", "horse", "norse", "computer", "", "!"] {
+        assert_eq!(Hash::new(word).fold_initial_vowel(), Hash::new(word));
+    }
+    assert!(Hash::new("jesus").fold_initial_vowel() != folded);
+    assert_eq!(folded.fold_initial_vowel(), folded);
+
+    // The first trailing phone of long words overflows into the first byte, setting its vowel bit.
+    for &word in &["crnjolztuhlkbkm", "kzroacpfypssthv", "krywrzjdhdyipxs"] {
+        assert!(u64::from(Hash::new(word)) & 1 << 63 != 0);
+        assert_eq!(Hash::new(word).fold_initial_vowel(), Hash::new(word), "{}", word);
+    }
+    for word in random_words(1000) {
+        if !word.starts_with(|x| "aeiouyAEIOUY".contains(x)) {
+            assert_eq!(Hash::new(&word).fold_initial_vowel(), Hash::new(&word), "{}", word);
+        }
+    }
+}
+
+/// Generate pseudorandom words of ASCII letters, punctuation, and non-ASCII characters.
+fn random_words(n: usize) -> Vec<String> {
+    const CHARS: &[char] = &[
+        'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r',
+        's', 't', 'u', 'v', 'w', 'x', 'y', 'z', 'A', 'E', 'K', 'R', 'S', 'Z', '-', '\'', '!', '@',
+        '`', '0', '9', ' ', 'ß', 'é', 'ü', 'ø', 'ÿ', 'Ł', 'ł', '日',
+    ];
+
+    // A linear congruential generator.
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    let mut next = move || {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (state >> 33) as usize
+    };
+
+    (0..n)
+        .map(|_| {
+            let len = next() % 24;
+            (0..len).map(|_| CHARS[next() % CHARS.len()]).collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_reference() {
+    let mut words = random_words(100_000);
+    words.extend(["", "a", "computer", "supercalifragilisticexpialidocious", "riiiindom"].iter().map(|x| x.to_string()));
+
+    if let Ok(dict) = std::fs::read_to_string("/usr/share/dict/words") {
+        words.extend(dict.lines().map(|x| x.to_owned()));
+    }
+
+    for word in &words {
+        assert_eq!(reference::hash(word), Hash::new(word), "{:?}", word);
+    }
+}
+
+#[test]
+fn test_dist_no_discriminant() {
+    // Differing only in the discriminant.
+    for &(a, b) in &[("bannana", "panana"), ("kat", "gat"), ("fast", "vast"), ("mice", "wice")] {
+        let diff = Hash::new(a) - Hash::new(b);
+        assert!(diff.dist() > 0);
+        assert_eq!(diff.dist_no_discriminant(), 0, "{} {}", a, b);
+    }
+
+    // Differing in other properties.
+    for &(a, b) in &[("horse", "norse"), ("lizzard", "wizzard"), ("jumpo", "jumbo")] {
+        let diff = Hash::new(a) - Hash::new(b);
+        assert!(diff.dist_no_discriminant() > 0);
+        assert!(diff.dist_no_discriminant() <= diff.dist());
+    }
+}
+
+#[test]
+#[cfg(feature = "weights-graduated")]
+fn test_neighbors() {
+    use search::neighbors;
+
+    let words = ["jumbo", "horse", "jumpo", "maier", "meyer", "norse", "computer", "jesus", "jeuses"];
+    let dictionary: Vec<(Hash, &str)> = words.iter().map(|&x| (Hash::new(x), x)).collect();
+
+    // "jumbo" is at distance 13 from "jumpo".
+    assert_eq!(neighbors("jumpo", 13, &dictionary), ["jumbo", "jumpo"]);
+    assert_eq!(neighbors("jumpo", 12, &dictionary), ["jumpo"]);
+    assert_eq!(neighbors("meier", 0, &dictionary), ["maier", "meyer"]);
+    assert!(neighbors("xylophone", 15, &dictionary).is_empty());
+    assert_eq!(neighbors("jumpo", 1000, &dictionary), words);
+
+    for &max_dist in &[0, 13, 15, 40, 100] {
+        let expected: Vec<&str> = words.iter().cloned().filter(|x| (Hash::new("jesus") - Hash::new(x)).dist() <= max_dist).collect();
+        assert_eq!(neighbors("jesus", max_dist, &dictionary), expected);
+    }
+}
+
+#[test]
+fn test_dist_profile() {
+    for word in random_words(1000).chunks(2) {
+        let diff = Hash::new(&word[0]) - Hash::new(&word[1]);
+
+        assert_eq!(diff.weighted(&weights::DEFAULT), diff.dist());
+        assert_eq!(diff.dist_profile(WeightProfile::Graduated), diff.weighted(&weights::GRADUATED));
+        assert_eq!(diff.dist_profile(WeightProfile::Flat), diff.hamming());
+        assert!(diff.dist_profile(WeightProfile::FrontHeavy) >= diff.hamming());
+        assert!(diff.dist_profile(WeightProfile::TailHeavy) >= diff.hamming());
+    }
+
+    // The first phone dominates the front heavy profile, the last the tail heavy.
+    let first = Hash::new("horse") - Hash::new("norse");
+    let last = Hash::new("lol") - Hash::new("lulz");
+    assert!(first.dist_profile(WeightProfile::FrontHeavy) > first.dist_profile(WeightProfile::TailHeavy));
+    assert!(last.dist_profile(WeightProfile::FrontHeavy) < last.dist_profile(WeightProfile::TailHeavy));
+}
+
+#[test]
+fn test_closest_pair() {
+    use search::closest_pair;
+
+    #[cfg(feature = "weights-graduated")]
+    {
+        let hashes: Vec<Hash> = ["youtube", "reddit", "jumbo", "horse", "jumpo", "computer", "norse"].iter().map(|x| Hash::new(x)).collect();
+        assert_eq!(closest_pair(&hashes), Some((2, 4, 13)));
+    }
+
+    // Compare to the full scan.
+    let words = random_words(300);
+    let hashes: Vec<Hash> = words.iter().map(|x| Hash::new(x)).collect();
+    for &n in &[0, 1, 2, 3, 10, 50, 300] {
+        let hashes = &hashes[..n];
+
+        let mut expected: Option<(u32, usize, usize)> = None;
+        for i in 0..n {
+            for j in i + 1..n {
+                let candidate = ((hashes[i] - hashes[j]).dist(), i, j);
+                if expected.map_or(true, |x| candidate < x) {
+                    expected = Some(candidate);
+                }
+            }
+        }
+
+        assert_eq!(closest_pair(hashes), expected.map(|(dist, i, j)| (i, j, dist)));
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_par_index() {
+    use index::PhoneticIndex;
+
+    let mut words = random_words(5000);
+    words.extend(words.clone());
+    let index = PhoneticIndex::from_words(&words);
+    let par_index = PhoneticIndex::par_build(&words);
+    assert_eq!(par_index.len(), index.len());
+
+    for query in words.iter().take(50).chain(&["jumpo".to_owned(), "".to_owned()]) {
+        for &max_dist in &[0, 15, 100] {
+            assert_eq!(index.par_lookup(query, max_dist), index.lookup(query, max_dist));
+            assert_eq!(par_index.lookup(query, max_dist), index.lookup(query, max_dist));
+        }
+    }
+}
+
+#[cfg(all(feature = "nightly", feature = "rayon"))]
+#[bench]
+fn bench_lookup(b: &mut Bencher) {
+    let index = index::PhoneticIndex::from_words(random_words(200_000));
+    b.iter(|| index.lookup(test::black_box("computer"), 40).len());
+}
+
+#[cfg(all(feature = "nightly", feature = "rayon"))]
+#[bench]
+fn bench_par_lookup(b: &mut Bencher) {
+    let index = index::PhoneticIndex::from_words(random_words(200_000));
+    b.iter(|| index.par_lookup(test::black_box("computer"), 40).len());
+}
+
+#[test]
+#[cfg(feature = "weights-graduated")]
+fn test_classify() {
+    for &(a, b) in &[("maier", "meyer"), ("smith", "smyth"), ("jesus", "jesus")] {
+        assert_eq!((Hash::new(a) - Hash::new(b)).classify(), Match::Exact, "{} {}", a, b);
+    }
+    for &(a, b) in &[("bannana", "panana"), ("fast", "vast"), ("hello", "hellou")] {
+        assert_eq!((Hash::new(a) - Hash::new(b)).classify(), Match::Homophone, "{} {}", a, b);
+    }
+    for &(a, b) in &[("jumpo", "jumbo"), ("nite", "night")] {
+        assert_eq!((Hash::new(a) - Hash::new(b)).classify(), Match::Similar, "{} {}", a, b);
+    }
+    for &(a, b) in &[("horse", "norse"), ("computer", "komputer"), ("lizzard", "wizzard")] {
+        assert_eq!((Hash::new(a) - Hash::new(b)).classify(), Match::Different, "{} {}", a, b);
+    }
+    assert!(Match::Exact < Match::Homophone && Match::Similar < Match::Different);
+}
+
+#[test]
+fn test_english() {
+    for &(silent, plain) in &[("knight", "night"), ("gnat", "nat"), ("psych", "sych"), ("write", "rite"), ("Knot", "not")] {
+        assert!(Hash::new(silent) != Hash::new(plain));
+        assert_eq!(Hash::new_lang(silent, Lang::English), Hash::new_lang(plain, Lang::English), "{} {}", silent, plain);
+        assert_eq!(Hash::new_lang(silent, Lang::English), Hash::new(plain), "{} {}", silent, plain);
+    }
+
+    // Only initial clusters are stripped.
+    assert_eq!(Lang::English.preprocess("unknown"), "unknown");
+    assert_eq!(Lang::English.preprocess("Kite"), "kite");
+    assert_eq!(Lang::English.preprocess("k"), "k");
+}
+
+#[test]
+fn test_german() {
+    assert_eq!(Hash::new_lang("Straße", Lang::German), Hash::new_lang("Strasse", Lang::German));
+    // Without the mode, the ß is skipped, which matters when the s would be kept.
+    assert!(Hash::new("Fuße") != Hash::new("Fusse"));
+    assert_eq!(Hash::new_lang("Fuße", Lang::German), Hash::new_lang("Fusse", Lang::German));
+    assert_eq!(Hash::new_lang("STRASSE", Lang::German), Hash::new("strasse"));
+    assert_eq!(Hash::new_lang("Müller", Lang::German), Hash::new_lang("Mueller", Lang::German));
+    assert_eq!(Hash::new_lang("Ölberg", Lang::German), Hash::new("oelberg"));
+
+    assert_eq!(Lang::German.preprocess("Größe"), "groesse");
+    assert_eq!(Lang::German.preprocess("GRÜSSE"), "gruesse");
+}
+
+#[test]
+fn test_semitic() {
+    let muhammad = Hash::new_lang("Muhammad", Lang::Semitic);
+    for &x in &["Mohammed", "Muhamed", "Mohamad", "Mohammad", "Muhammed", "Mohamed"] {
+        assert_eq!(Hash::new_lang(x, Lang::Semitic), muhammad);
+    }
+    assert!(Hash::new("Muhammad") != Hash::new("Mohammed"));
+
+    assert_eq!(Hash::new_lang("Umar", Lang::Semitic), Hash::new_lang("Omar", Lang::Semitic));
+    assert_eq!(Hash::new_lang("Yusuf", Lang::Semitic), Hash::new_lang("Yousef", Lang::Semitic));
+    assert_eq!(Hash::new_lang("Hussein", Lang::Semitic), Hash::new_lang("Husain", Lang::Semitic));
+    assert_eq!(Hash::new_lang("Ka'ba", Lang::Semitic), Hash::new_lang("Kaaba", Lang::Semitic));
+    // Different roots still differ.
+    assert!(Hash::new_lang("Ahmad", Lang::Semitic) != muhammad);
+    assert!(Hash::new_lang("Karim", Lang::Semitic) != Hash::new_lang("Kamal", Lang::Semitic));
+
+    assert_eq!(Lang::Semitic.preprocess("Mohammed"), "mhmd");
+    assert_eq!(Lang::Semitic.preprocess("ʿUmar"), "amr");
+    // Repeated consonants separated by vowels are not doubled.
+    assert_eq!(Lang::Semitic.preprocess("baba"), "bb");
+    assert_eq!(Lang::Semitic.preprocess("dadi"), "dd");
+    assert_eq!(Lang::Semitic.preprocess("Zalzala"), "zlzl");
+}
+
+#[test]
+fn test_distance_matrix() {
+    use search::distance_matrix;
+
+    let words = ["jumbo", "horse", "jumpo", "norse", "maier", "meyer", ""];
+    let matrix = distance_matrix(&words);
+
+    assert_eq!(matrix.len(), words.len());
+    for i in 0..words.len() {
+        assert_eq!(matrix[i].len(), words.len());
+        assert_eq!(matrix[i][i], 0);
+        for j in 0..words.len() {
+            assert_eq!(matrix[i][j], matrix[j][i]);
+            assert_eq!(matrix[i][j], (Hash::new(words[i]) - Hash::new(words[j])).dist());
+        }
+    }
+    assert_eq!(matrix[4][5], 0);
+
+    assert!(distance_matrix(&[]).is_empty());
+}
+
+#[test]
+fn test_soft_filter() {
+    let config = HashConfig::new().soft(true);
+
+    assert_eq!(Hash::new("aaa"), Hash::new("a"));
+    assert!(config.build_hash("aaa") != config.build_hash("a"));
+    assert!(config.build_hash("aaa") != config.build_hash("aa"));
+    assert!(config.build_hash("hello") != config.build_hash("helo"));
+    assert!((config.build_hash("hello") - config.build_hash("helo")).similar());
+    // Only the last byte is nudged.
+    assert_eq!(config.build_hash("aaa").hash >> 8, Hash::new("a").hash >> 8);
+
+    let tables = raw::Tables::default();
+    for prev in 0..=255 {
+        for x in 0..=255 {
+            let soft = raw::filter_soft(prev, x);
+            assert_eq!(tables.filter_soft(prev, x), soft);
+            // Pushed phones are the ones `filter` pushes.
+            assert_eq!(soft.filter(|&y| y & 1 != prev & 1), raw::filter(prev, x));
+        }
+    }
+}
+
+#[test]
+fn test_lexicon_trie() {
+    use index::LexiconTrie;
+
+    let mut words = random_words(5000);
+    words.extend(words[..100].to_vec());
+    let trie = LexiconTrie::from_words(&words);
+    let hashes: Vec<Hash> = words.iter().map(|x| Hash::new(x)).collect();
+
+    let mut distinct = words.clone();
+    distinct.sort();
+    distinct.dedup();
+    assert_eq!(trie.len(), distinct.len());
+
+    for query in words.iter().take(50).chain(&["jumpo".to_owned(), "".to_owned()]) {
+        let hash = Hash::new(query);
+        for &max_dist in &[0, 15, 50, 200] {
+            // Brute force.
+            let mut expected: Vec<(u32, &str)> = hashes
+                .iter()
+                .zip(&words)
+                .map(|(&x, word)| ((hash - x).dist(), &word[..]))
+                .filter(|&(dist, _)| dist <= max_dist)
+                .collect();
+            expected.sort();
+            expected.dedup();
+            let expected: Vec<&str> = expected.into_iter().map(|(_, x)| x).collect();
+
+            assert_eq!(trie.lookup(query, max_dist), expected);
+        }
+    }
+
+    assert!(LexiconTrie::new().is_empty());
+    assert!(LexiconTrie::new().lookup("jumbo", 1000).is_empty());
+}
+
+#[cfg(feature = "nightly")]
+#[bench]
+fn bench_trie_lookup(b: &mut Bencher) {
+    let trie = index::LexiconTrie::from_words(random_words(200_000));
+    b.iter(|| trie.lookup(test::black_box("computer"), 15).len());
+}
+
+#[cfg(feature = "nightly")]
+#[bench]
+fn bench_linear_lookup(b: &mut Bencher) {
+    let words = random_words(200_000);
+    let dictionary: Vec<(Hash, &str)> = words.iter().map(|x| (Hash::new(x), &x[..])).collect();
+    b.iter(|| search::neighbors(test::black_box("computer"), 15, &dictionary).len());
+}
+
+#[test]
+fn test_split_camel_case() {
+    use phrase::split_camel_case;
+
+    assert_eq!(split_camel_case("getUserName").collect::<Vec<_>>(), ["get", "User", "Name"]);
+    assert_eq!(split_camel_case("NewYork").collect::<Vec<_>>(), ["New", "York"]);
+    assert_eq!(split_camel_case("newyork").collect::<Vec<_>>(), ["newyork"]);
+    assert_eq!(split_camel_case("aB").collect::<Vec<_>>(), ["a", "B"]);
+    assert_eq!(split_camel_case("").count(), 0);
+
+    let config = HashConfig::new().split_camel_case(true);
+    assert_eq!(config.build_hash("NewYork"), config.build_hash("new york"));
+    assert_eq!(config.build_hash("NewYork"), config.build_hash("#new-york"));
+    assert_eq!(config.build_hash("getUserName"), config.build_hash("get user name"));
+    // The initial of a word is kept, even if it repeats the previous phone.
+    assert_eq!(Hash::new("catTail"), Hash::new("cattail"));
+    assert!(config.build_hash("catTail") != config.build_hash("cattail"));
+    assert_eq!(config.build_hash("catTail"), config.build_hash("cat tail"));
+    assert_eq!(config.build_hash("jumbo"), Hash::new("jumbo"));
+    assert_eq!(config.build_hash(""), Hash::new(""));
+
+    // Off by default.
+    assert_eq!(HashConfig::new().build_hash("get user name"), Hash::new("getusername"));
+}
+
+#[test]
+fn test_expand() {
+    use search::expand;
+
+    for &word in &["jumbo", "", "horse"] {
+        let hash = Hash::new(word);
+        let hashes = expand(word, 2000);
+        assert_eq!(hashes.len(), 2000);
+
+        let mut prev = 0;
+        for &x in &hashes {
+            let dist = (x - hash).dist();
+            assert!((x - hash).similar());
+            assert!(dist > 0 && dist >= prev);
+            prev = dist;
+        }
+
+        let mut distinct = hashes.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), hashes.len());
+    }
+
+    // The closest perturbations flip the last phone.
+    #[cfg(feature = "weights-graduated")]
+    assert_eq!(expand("jumbo", 8), (0..8).map(|i| Hash { hash: Hash::new("jumbo").hash ^ 1 << i }).collect::<Vec<_>>());
+    assert!(expand("jumbo", 0).is_empty());
+}
+
+#[test]
+fn test_coverage() {
+    for &(short, long) in &[("dept", "department"), ("govt", "government"), ("intl", "international")] {
+        let short = Hash::new(short);
+        let long = Hash::new(long);
+
+        assert!(Difference::coverage(short, long) >= 0.5);
+        assert!(Difference::coverage(short, long) > Difference::coverage(long, short));
+        assert!(Difference::coverage(short, long) > Difference::coverage(short, Hash::new("horse")));
+    }
+
+    assert_eq!(Difference::coverage(Hash::new("dept"), Hash::new("department")), 1.0);
+    assert_eq!(Difference::coverage(Hash::new("jumbo"), Hash::new("jumbo")), 1.0);
+    assert_eq!(Difference::coverage(Hash::new(""), Hash::new("jumbo")), 1.0);
+    assert_eq!(Difference::coverage(Hash::new("jumbo"), Hash::new("")), 0.0);
+}
+
+#[test]
+fn test_default_weights() {
+    #[cfg(not(any(feature = "weights-flat", feature = "weights-front-heavy")))]
+    let profile = WeightProfile::Graduated;
+    #[cfg(feature = "weights-flat")]
+    let profile = WeightProfile::Flat;
+    #[cfg(feature = "weights-front-heavy")]
+    let profile = WeightProfile::FrontHeavy;
+
+    let words = random_words(200);
+    for pair in words.windows(2) {
+        let diff = Hash::new(&pair[0]) - Hash::new(&pair[1]);
+        assert_eq!(diff.dist(), diff.dist_profile(profile));
+    }
+}
+
+#[test]
+fn test_bands() {
+    let shared = |a: &str, b: &str, num_bands| {
+        let a = Hash::new(a).bands(num_bands);
+        let b = Hash::new(b).bands(num_bands);
+        a.iter().zip(&b).filter(|&(x, y)| x == y).count()
+    };
+
+    for &num_bands in &[4, 8, 16] {
+        #[cfg(feature = "weights-graduated")]
+        assert!(shared("computer", "komputer", num_bands) > shared("computer", "jesus", num_bands));
+        assert_eq!(shared("maier", "meyer", num_bands), num_bands as usize);
+
+        // On average, close pairs share more bands than distant pairs.
+        let bands: Vec<(Hash, Vec<u32>)> =
+            random_words(500).iter().map(|x| (Hash::new(x), Hash::new(x).bands(num_bands))).collect();
+        let (mut close, mut distant) = ((0, 0), (0, 0));
+        for (a, a_bands) in &bands {
+            for (b, b_bands) in &bands {
+                let dist = (*a - *b).dist();
+                let shared = a_bands.iter().zip(b_bands).filter(|&(x, y)| x == y).count();
+                if dist <= 30 {
+                    close = (close.0 + shared, close.1 + 1);
+                } else if dist >= 100 {
+                    distant = (distant.0 + shared, distant.1 + 1);
+                }
+            }
+        }
+        #[cfg(feature = "weights-graduated")]
+        assert!(close.0 as f64 / close.1 as f64 > distant.0 as f64 / distant.1 as f64);
+    }
+
+    // The bands reassemble into the hash.
+    let hash = Hash::new("jumbo");
+    assert_eq!(hash.bands(2), [(hash.hash >> 32) as u32, hash.hash as u32]);
+    for num_bands in 2..=64 {
+        let bands = hash.bands(num_bands);
+        assert_eq!(bands.len(), num_bands as usize);
+
+        let mut res = 0u64;
+        for (i, &band) in bands.iter().enumerate() {
+            let bits = 64 / num_bands + ((i as u32) < 64 % num_bands) as u32;
+            assert!((band as u64) < 1 << bits);
+            res = res << bits | band as u64;
+        }
+        assert_eq!(res, hash.hash);
+    }
+}
+
+#[test]
+fn test_empty() {
+    // The last byte of a hash is zero or a phone, neither of which is 0xFF.
+    assert!((0..=255).all(|x| raw::phone(x) != Some(0xFF)));
+
+    let mut words = random_words(100_000);
+    words.extend([
+        "", "a", "jumbo", "computer", "supercalifragilisticexpialidocious", "riiiindom", "Émile",
+        "Größe", "smørrebrød", "þorn", "ÿÿÿÿÿÿÿÿÿ", "÷÷÷", "yiyiyiyiyi", "qqqqqqqqq", "iuiuiuiuiu",
+        "O'Neill", "Jean-Luc", "McDonald", "xylophone", "rhythm", "zzzzz", "!!!", "42",
+    ].iter().map(|x| x.to_string()));
+    for word in &words {
+        assert!(!Hash::new(word).is_empty(), "{}", word);
+        assert!(!HashConfig::new().build_hash(word).is_empty(), "{}", word);
+    }
+
+    assert!(Hash::empty().is_empty());
+    assert_eq!(u64::from(Hash::empty()), u64::MAX);
+}
+
+#[test]
+fn test_dist_both() {
+    let profiles = [
+        WeightProfile::Graduated,
+        WeightProfile::Flat,
+        WeightProfile::FrontHeavy,
+        WeightProfile::TailHeavy,
+    ];
+
+    let words = random_words(500);
+    for pair in words.windows(2) {
+        let diff = Hash::new(&pair[0]) - Hash::new(&pair[1]);
+        for a in &profiles {
+            for b in &profiles {
+                let (a, b) = (a.weights(), b.weights());
+                assert_eq!(diff.dist_both(&a, &b), (diff.weighted(&a), diff.weighted(&b)));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_from_ipa() {
+    for &(ipa, word) in &[
+        ("hɔːrs", "horse"),
+        ("ʃɪp", "ship"),
+        ("ˈlɪzərd", "lizard"),
+        ("[ˈwɔtər]", "water"),
+        ("kəmˈpjuːtər", "komputer"),
+    ] {
+        assert_eq!(Hash::from_ipa(ipa), Hash::new(word), "{} {}", ipa, word);
+    }
+
+    for &(ipa, word) in &[("/ˈdʒʌmboʊ/", "jumbo"), ("tʃɛər", "chair"), ("mɪkəl", "michael")] {
+        let (dist, horse) = (Hash::from_ipa(ipa) - Hash::new(word), Hash::from_ipa(ipa) - Hash::new("horse"));
+        assert!(dist.dist() < horse.dist(), "{} {}", ipa, word);
+        #[cfg(feature = "weights-graduated")]
+        assert!(dist.similar() && !horse.similar(), "{} {}", ipa, word);
+    }
+
+    // Affricates.
+    assert_eq!(Hash::from_ipa("t͡ʃɛər"), Hash::from_ipa("tʃɛər"));
+    assert_eq!(Hash::from_ipa("ʧɛər"), Hash::from_ipa("tʃɛər"));
+    assert!(Hash::from_ipa("tʃɛər") != Hash::from_ipa("tɛər"));
+
+    assert_eq!(Hash::from_ipa(""), Hash::new(""));
+    assert_eq!(Hash::from_ipa("ˈ"), Hash::new(""));
+}
+
+#[test]
+fn test_consensus() {
+    // An outlier does not dominate the consensus.
+    let consensus = Hash::consensus(&["jumbo", "jumbo", "jumpo", "jumbo", "horse"]);
+    assert_eq!(consensus, Hash::new("jumbo"));
+
+    let spellings = ["catherine", "katherine", "kathryn", "katharine", "xavier"];
+    let consensus = Hash::consensus(&spellings);
+    assert!((consensus - Hash::new("katherine")).dist() < (Hash::new("xavier") - Hash::new("katherine")).dist());
+    assert!((consensus - Hash::new("xavier")).dist() > (consensus - Hash::new("katherine")).dist());
+
+    // Ties favor zero.
+    let (a, b) = (Hash::new("jumbo"), Hash::new("horse"));
+    assert_eq!(Hash::consensus(&["jumbo", "horse"]).hash, a.hash & b.hash);
+    assert_eq!(Hash::consensus(&[]).hash, 0);
+    assert_eq!(Hash::consensus(&["lizzard"]), Hash::new("lizzard"));
+}
+
+#[cfg(feature = "lru")]
+#[test]
+fn test_caching_hasher() {
+    let words = random_words(2000);
+
+    let mut hasher = CachingHasher::new(100);
+    let mut lang_hasher = CachingHasher::with_lang(100, Lang::English);
+    // Every word is hashed twice, first uncached, and then cached.
+    for word in words.iter().chain(words.iter().rev()) {
+        assert_eq!(hasher.hash(word), Hash::new(word));
+        assert_eq!(hasher.hash(word), Hash::new(word));
+        assert_eq!(lang_hasher.hash(word), Hash::new_lang(word, Lang::English));
+        assert!(hasher.len() <= 100);
+    }
+    assert_eq!(hasher.len(), 100);
+    assert!(CachingHasher::new(1).is_empty());
+}
+
+#[test]
+fn test_dist_initial_tolerant() {
+    for &(a, b) in &[("catherine", "katherine"), ("yusuf", "jusuf"), ("computer", "komputer")] {
+        let diff = Hash::new(a) - Hash::new(b);
+        assert!(diff.dist_initial_tolerant(0) < diff.dist(), "{} {}", a, b);
+        #[cfg(feature = "weights-graduated")]
+        assert!(!diff.similar() && diff.dist_initial_tolerant(10) < 15, "{} {}", a, b);
+    }
+
+    // Only the first letter is tolerated.
+    assert_eq!((Hash::new("horse") - Hash::new("norse")).dist_initial_tolerant(0), 0);
+    #[cfg(feature = "weights-graduated")]
+    assert!((Hash::new("jumbo") - Hash::new("horse")).dist_initial_tolerant(10) >= 15);
+
+    let words = random_words(200);
+    for pair in words.windows(2) {
+        let diff = Hash::new(&pair[0]) - Hash::new(&pair[1]);
+        assert_eq!(diff.dist_initial_tolerant(u32::MAX), diff.dist());
+        assert!(diff.dist_initial_tolerant(10) <= diff.dist());
+        let mut weights = weights::DEFAULT;
+        weights[7] = 0;
+        assert_eq!(diff.dist_initial_tolerant(0), diff.weighted(&weights));
+    }
+}
+
+#[test]
+fn test_normalized() {
+    assert_eq!((Hash::new("jumbo") - Hash::new("jumbo")).normalized(), 1.0);
+    assert_eq!((Hash { hash: 0 } - Hash { hash: !0 }).normalized(), 0.0);
+
+    let words = random_words(200);
+    for pair in words.windows(2) {
+        let diff = Hash::new(&pair[0]) - Hash::new(&pair[1]);
+        assert!((0.0..=1.0).contains(&diff.normalized()));
+    }
+    assert!((Hash::new("jumbo") - Hash::new("jumpo")).normalized() > (Hash::new("jumbo") - Hash::new("horse")).normalized());
+}
+
+#[test]
+fn test_cluster_affinity() {
+    use search::cluster_affinity;
+
+    let hashes = |words: &[&str]| words.iter().map(|x| Hash::new(x)).collect::<Vec<_>>();
+    let katherines = hashes(&["katherine", "kathryn", "katharine", "cathy"]);
+    let smiths = hashes(&["smith", "smyth", "smithe", "schmidt"]);
+
+    for &query in &["catherine", "kathrine"] {
+        let query = Hash::new(query);
+        assert!(cluster_affinity(query, &katherines) > cluster_affinity(query, &smiths));
+    }
+    for &query in &["smitt", "smid"] {
+        let query = Hash::new(query);
+        assert!(cluster_affinity(query, &smiths) > cluster_affinity(query, &katherines));
+    }
+
+    assert_eq!(cluster_affinity(Hash::new("smith"), &hashes(&["smith", "smyth"])), 1.0);
+    assert_eq!(cluster_affinity(Hash::new("smith"), &[]), 0.0);
+}
+
+#[test]
+fn test_repetitive_input() {
+    use std::time::{Duration, Instant};
+
+    let start = Instant::now();
+
+    // A run of a letter collapses into at most two phones, the first letter itself and one
+    // trailing phone.
+    for x in b'a'..=b'z' {
+        let run = String::from_utf8(vec![x; 1 << 16]).unwrap();
+        let hash = Hash::new(&run[..2]);
+
+        assert_eq!(Hash::new(&run), hash, "{}", x as char);
+        assert_eq!(HashConfig::new().build_hash(&run), hash, "{}", x as char);
+        let mut builder = HashBuilder::new();
+        builder.write(run.as_bytes());
+        assert_eq!(builder.finish(), hash, "{}", x as char);
+    }
+
+    let run = "a".repeat(1 << 20);
+    assert_eq!(Hash::new(&run), Hash::new("a"));
+    assert_eq!(Hash::new(&"ab".repeat(1 << 19)), Hash::new(&"ab".repeat(8)));
+
+    // Every byte is visited once, so this takes a fraction of a second, even unoptimized.
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+fn test_strict() {
+    let strict = HashConfig::new().strict(true);
+    let central = strict.clone().lang(Lang::CentralEuropean);
+
+    // Unmapped scripts are rejected.
+    for &(word, x) in &[("東京", '東'), ("Москва", 'М'), ("tokyo東京", '東')] {
+        assert_eq!(Hash::try_new(word, &strict), Err(UnmappedChar(x)));
+        assert_eq!(Hash::try_new(word, &central), Err(UnmappedChar(x)));
+        assert_eq!(Hash::try_new(word, &HashConfig::new()), Ok(Hash::new(word)));
+    }
+
+    // The language mode maps the Latin-extended letters.
+    for &word in &["Szabó", "Łódź", "Kovács", "Dvořák"] {
+        assert!(Hash::try_new(word, &strict).is_err(), "{}", word);
+        assert_eq!(Hash::try_new(word, &central), Ok(Hash::new_lang(word, Lang::CentralEuropean)));
+        assert_eq!(Hash::try_new(word, &HashConfig::new()), Ok(Hash::new(word)));
+    }
+
+    // ASCII is accepted.
+    assert_eq!(Hash::try_new("new york, 1999!", &strict), Ok(Hash::new("new york, 1999!")));
+    assert_eq!(central.build_hash("東京"), Hash::new("東京"));
+    assert!(UnmappedChar('東').to_string().contains("U+6771"));
+}
+
+#[test]
+fn test_combined_similarity() {
+    use search::combined_similarity;
+
+    let words = random_words(200);
+    for pair in words.windows(2) {
+        let (a, b) = (&pair[0], &pair[1]);
+        let normalized = (Hash::new(a) - Hash::new(b)).normalized();
+        let jaccard = raw::phone_jaccard(a, b);
+
+        assert_eq!(combined_similarity(a, b, 1.0), normalized);
+        assert_eq!(combined_similarity(a, b, 0.0), jaccard);
+        let half = combined_similarity(a, b, 0.5);
+        assert!(half >= normalized.min(jaccard) - 1e-12 && half <= normalized.max(jaccard) + 1e-12);
+    }
+
+    assert_eq!(combined_similarity("maier", "meyer", 0.3), 1.0 * 0.3 + raw::phone_jaccard("maier", "meyer") * 0.7);
+}
+
+#[test]
+fn test_dedup_ratio() {
+    for x in 0..=255 {
+        for prev in 0..=255 {
+            assert_eq!(raw::filter_explained(prev, x).ok(), raw::filter(prev, x));
+        }
+    }
+
+    assert!(raw::dedup_ratio("committee") > raw::dedup_ratio("printer"));
+    assert!(raw::dedup_ratio("balloon") > raw::dedup_ratio("lizard"));
+    // Only the discriminants are compared, so repeated letters alone do not make for a high ratio.
+    assert!(raw::dedup_ratio("bookkeeper") < raw::dedup_ratio("computer"));
+    assert_eq!(raw::dedup_ratio("aaaa"), 1.0);
+    assert_eq!(raw::dedup_ratio("a"), 0.0);
+    assert_eq!(raw::dedup_ratio(""), 0.0);
+    assert_eq!(raw::dedup_ratio("k-t"), raw::dedup_ratio("kt"));
+    assert!((0.0..=1.0).contains(&raw::dedup_ratio("mississippi")));
+}
+
+#[test]
+fn test_fold_after() {
+    let long = "internationalization";
+    let tail = "internationalizing";
+    assert_eq!(Hash::new(long), Hash::new(tail));
+
+    let mut hashes = Vec::new();
+    for n in 1..=8 {
+        let config = HashConfig::new().fold_after(n);
+        let hash = config.build_hash(long);
+
+        // The first letter is followed by only `n` bytes.
+        if n < 8 {
+            assert_eq!(hash.hash >> 56, raw::map_first(b'i') as u64);
+            assert_eq!((hash.hash & 0x00FF_FFFF_FFFF_FFFF) >> (8 * n), 0, "{}", n);
+        }
+        assert!(config.build_hash(long) != config.build_hash(tail), "{}", n);
+        hashes.push(hash);
+    }
+    // Every fold point gives a different hash.
+    let mut distinct = hashes.clone();
+    distinct.sort();
+    distinct.dedup();
+    assert_eq!(distinct.len(), hashes.len());
+
+    // Words with no more phones than the fold point are unaffected.
+    for word in random_words(1000) {
+        let mut prev = 0;
+        let mut pushed = 0;
+        for &x in word.as_bytes().iter().skip(1) {
+            if let Some(x) = raw::filter(prev, x) {
+                prev = x;
+                pushed += 1;
+            }
+        }
+
+        for n in 1..=8 {
+            if pushed <= n {
+                assert_eq!(HashConfig::new().fold_after(n as u8).build_hash(&word), Hash::new(&word));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_symmetric_dist() {
+    let dist = |a: &str, b: &str| {
+        let (fwd_a, rev_a) = Hash::symmetric(a);
+        let (fwd_b, rev_b) = Hash::symmetric(b);
+        assert_eq!(fwd_a, Hash::new(a));
+        assert_eq!(rev_a, Hash::rhyme_key(a));
+        (Difference::symmetric_dist(fwd_a, rev_a, fwd_b, rev_b), (fwd_a - fwd_b).dist(), (rev_a - rev_b).dist())
+    };
+
+    // Differing in the beginning.
+    #[cfg(feature = "weights-graduated")]
+    for &(a, b) in &[("catherine", "katherine"), ("horse", "norse"), ("philip", "filip")] {
+        let (symmetric, forward, _) = dist(a, b);
+        assert!(symmetric < forward, "{} {}", a, b);
+    }
+    // Differing in the ending.
+    #[cfg(feature = "weights-graduated")]
+    for &(a, b) in &[("nation", "nations"), ("jumbo", "jumbz"), ("smith", "smithers")] {
+        let (symmetric, _, reverse) = dist(a, b);
+        assert!(symmetric < reverse, "{} {}", a, b);
+    }
+
+    let words = random_words(200);
+    for pair in words.windows(2) {
+        let (symmetric, forward, reverse) = dist(&pair[0], &pair[1]);
+        assert_eq!(symmetric, forward.min(reverse));
+    }
+}
+
+#[test]
+fn test_transcribe() {
+    // The transcription is approximate, but stable.
+    assert_eq!(raw::transcribe("computer"), "c-p-a-r");
+    assert_eq!(raw::transcribe("horse"), "h-r-s");
+    assert_eq!(raw::transcribe("lizard"), "l-i-z-r-d");
+    assert_eq!(raw::transcribe("bookkeeper"), "b-k-a-p-a-r");
+    assert_eq!(raw::transcribe("internationalization"), "i-t-a-r-n-t-a-i-z");
+    assert_eq!(raw::transcribe("maier"), raw::transcribe("meyer"));
+    assert_eq!(raw::transcribe(""), "");
+    assert_eq!(raw::transcribe("-"), "");
+}
+
+#[test]
+fn test_ngrams() {
+    let word = "telephone";
+    let ngrams: Vec<Hash> = Hash::ngrams(word, 4).collect();
+    assert_eq!(ngrams.len(), word.len() - 4 + 1);
+    for (i, &hash) in ngrams.iter().enumerate() {
+        assert_eq!(hash, Hash::new(&word[i..i + 4]));
+    }
+    assert_eq!(ngrams[4], Hash::new("phon"));
+
+    // Windows sharing their first letter share their first byte.
+    let phone: Vec<Hash> = Hash::ngrams("phonephone", 5).collect();
+    assert_eq!(phone[0], phone[5]);
+    assert_eq!(phone[0].hash >> 56, Hash::new("phony").hash >> 56);
+
+    // Characters, not bytes.
+    assert_eq!(Hash::ngrams("süß", 2).count(), 2);
+    assert_eq!(Hash::ngrams("tele", 4).count(), 1);
+    assert_eq!(Hash::ngrams("tele", 5).collect::<Vec<_>>(), [Hash::new("tele")]);
+    assert_eq!(Hash::ngrams("", 3).collect::<Vec<_>>(), [Hash::new("")]);
+}
+
+#[test]
+fn test_agreement() {
+    let max = (Hash::new("jumbo") - Hash::new("jumbo")).agreement();
+    assert_eq!(max, weights::DEFAULT.iter().sum::<u32>() * 8);
+    for &word in &["", "jumbo", "internationalization"] {
+        assert_eq!((Hash::new(word) - Hash::new(word)).agreement(), max);
+    }
+    assert_eq!((Hash { hash: 0 } - Hash { hash: !0 }).agreement(), 0);
+
+    let words = random_words(200);
+    for pair in words.windows(2) {
+        let diff = Hash::new(&pair[0]) - Hash::new(&pair[1]);
+        assert_eq!(diff.agreement() + diff.dist(), max);
+    }
+    assert!((Hash::new("jumbo") - Hash::new("jumpo")).agreement() > (Hash::new("jumbo") - Hash::new("horse")).agreement());
+}
+
+#[test]
+#[cfg(feature = "weights-graduated")]
+fn test_fit_threshold() {
+    use search::fit_threshold;
+
+    let pairs = |pairs: &[(&str, &str)]| pairs.iter().map(|&(a, b)| (Hash::new(a), Hash::new(b))).collect::<Vec<_>>();
+    let positives = pairs(&[("jumbo", "jumpo"), ("maier", "meyer"), ("hello", "hellou"), ("nite", "night")]);
+    let negatives = pairs(&[("jumbo", "horse"), ("computer", "jesus"), ("lizzard", "meyer"), ("horse", "norse")]);
+
+    // The positives are within 13, and the negatives are at least 41 apart.
+    assert!(positives.iter().all(|&(a, b)| (a - b).dist() <= 13));
+    assert!(negatives.iter().all(|&(a, b)| (a - b).dist() >= 41));
+    assert_eq!(fit_threshold(&positives, &negatives), 13);
+
+    // A negative among the positives is let in, when leaving it out costs more positives.
+    let mut noisy = negatives.clone();
+    noisy.push((Hash::new("jumbo"), Hash::new("jumbu")));
+    assert_eq!(fit_threshold(&positives, &noisy), 13);
+    // ... but not when it costs fewer.
+    assert_eq!(fit_threshold(&positives[1..3], &noisy), 1);
+
+    assert_eq!(fit_threshold(&[], &negatives), 0);
+    assert_eq!(fit_threshold(&positives, &[]), 13);
+}
+
+#[test]
+fn test_new_fine() {
+    // These collide under the coarse hash, but not the fine one.
+    for &(a, b) in &[("maier", "meyer"), ("mgr", "manager"), ("smith", "smyth"), ("rock", "rok")] {
+        assert_eq!(Hash::new(a), Hash::new(b), "{} and {}", a, b);
+        assert_ne!(Hash::new_fine(a), Hash::new_fine(b), "{} and {}", a, b);
+    }
+
+    // Case, punctuation and repeated letters are ignored.
+    assert_eq!(Hash::new_fine("O'Neill"), Hash::new_fine("ONEIL"));
+    assert_eq!(Hash::new_fine("ab"), Hash { hash: 0x8400000000000024 });
+    assert_eq!(Hash::new_fine(""), Hash::new(""));
+    // Only seven trailing phones are kept.
+    assert_eq!(Hash::new_fine("abcdefghij"), Hash::new_fine("abcdefghxyz"));
+}
+
+#[test]
+fn test_phonetic_anagrams() {
+    use raw::are_phonetic_anagrams;
+
+    assert!(are_phonetic_anagrams("listen", "silent"));
+    assert!(are_phonetic_anagrams("Dormitory", "dirty room"));
+    assert!(are_phonetic_anagrams("mat", "tom"));
+    assert!(are_phonetic_anagrams("", "--"));
+
+    // Every phone must occur equally often.
+    assert!(!are_phonetic_anagrams("listen", "silence"));
+    assert!(!are_phonetic_anagrams("listen", "listens"));
+    assert!(!are_phonetic_anagrams("tool", "lot"));
+    assert!(!are_phonetic_anagrams("cat", "tak"));
+}
+
+#[test]
+fn test_split_u32() {
+    assert_eq!(Hash { hash: 0x0123456789abcdef }.split_u32(), (0x01234567, 0x89abcdef));
+    assert_eq!(Hash::from_u32_pair(0x01234567, 0x89abcdef), Hash { hash: 0x0123456789abcdef });
+
+    for word in random_words(100).iter().map(|x| &**x).chain(vec!["", "a"]) {
+        let hash = Hash::new(word);
+        let (hi, lo) = hash.split_u32();
+        assert_eq!(Hash::from_u32_pair(hi, lo), hash);
+    }
+    let (hi, lo) = Hash::empty().split_u32();
+    assert!(Hash::from_u32_pair(hi, lo).is_empty());
+}
+
+#[test]
+fn test_matches_initialism() {
+    use phrase::matches_initialism;
+
+    assert!(matches_initialism("USA", "United States of America"));
+    assert!(matches_initialism("u.s.a.", "united states of america"));
+    assert!(matches_initialism("NASA", "National Aeronautics and Space Administration"));
+    assert!(matches_initialism("FBI", "Federal Bureau of Investigation"));
+    assert!(matches_initialism("DoJ", "Department of Justice"));
+    assert!(matches_initialism("DJ", "Department of Justice"));
+    assert!(matches_initialism("TUoT", "The University of Tokyo"));
+    assert!(matches_initialism("UoT", "The University of Tokyo"));
+
+    // Near-misses.
+    assert!(!matches_initialism("USA", "United Kingdom"));
+    assert!(!matches_initialism("FBI", "Federal Bureau"));
+    assert!(!matches_initialism("FBI", "Federal Bureau of Investigation Services"));
+    assert!(!matches_initialism("KIA", "Central Intelligence Agency"));
+    assert!(!matches_initialism("", "the"));
+    assert!(!matches_initialism("A", ""));
+}
+
+#[test]
+fn test_distance_quantiles() {
+    use stats::DistanceQuantiles;
+
+    let words = random_words(4000);
+    let differences: Vec<Difference> = words
+        .chunks(2)
+        .map(|x| Hash::new(&x[0]) - Hash::new(&x[1]))
+        .collect();
+    let mut dists: Vec<u32> = differences.iter().map(|x| x.dist()).collect();
+    dists.sort();
+
+    let ps = [0.01, 0.1, 0.5, 0.9, 0.99];
+    let mut quantiles = DistanceQuantiles::new(&ps);
+    assert!(quantiles.is_empty());
+    assert_eq!(quantiles.estimates(), [None; 5]);
+    for &difference in &differences {
+        quantiles.push(difference);
+    }
+    assert_eq!(quantiles.len(), differences.len());
+
+    for (&p, estimate) in ps.iter().zip(quantiles.estimates()) {
+        let exact = dists[(p * dists.len() as f64) as usize] as f64;
+        let error = (estimate.unwrap() - exact).abs();
+        assert!(error <= 0.01 * weights::MAX_DIST as f64, "{}: {} vs. {}", p, estimate.unwrap(), exact);
+    }
+
+    // Up to five distances, the quantiles are exact.
+    let mut quantiles = DistanceQuantiles::new(&[0.5]);
+    for &(a, b) in &[("jumbo", "jumpo"), ("maier", "meyer"), ("jumbo", "horse")] {
+        quantiles.push(Hash::new(a) - Hash::new(b));
+    }
+    let median = (Hash::new("jumbo") - Hash::new("jumpo")).dist()
+        .max((Hash::new("maier") - Hash::new("meyer")).dist())
+        .min((Hash::new("jumbo") - Hash::new("horse")).dist());
+    assert_eq!(quantiles.estimates(), [Some(median as f64)]);
+}
+
+#[test]
+fn test_new_masked() {
+    // Masking nothing changes nothing.
+    for word in random_words(200).iter().map(|x| &**x).chain(vec!["", "a", "bookkeepers"]) {
+        assert_eq!(Hash::new_masked(word, 0), Hash::new(word), "{}", word);
+        assert_eq!(Hash::new_masked(word, 1), Hash::new(word), "{}", word);
+    }
+
+    // m is nasal, and n is a nasal dental.
+    assert!(Hash::new_masked("bin", 2) != Hash::new_masked("bim", 2));
+    assert_eq!(Hash::new_masked("bin", 16), Hash::new_masked("bim", 16));
+    // Masking every property leaves only the discriminants.
+    assert!(Hash::new("bat") != Hash::new("bak"));
+    assert_eq!(Hash::new_masked("bat", 0xFF), Hash::new_masked("bak", 0xFF));
+    assert_eq!((Hash::new_masked("bat", 0xFF) - Hash::new("b")).xor(), 1);
+}
+
+#[test]
+fn test_dist_consonants_only() {
+    // Words only differing in the vowels.
+    for &(a, b) in &[("ellen", "allen"), ("iesus", "aesus"), ("olaf", "ulaf"), ("ebb", "abbe"), ("bana", "bani")] {
+        let diff = Hash::new(a) - Hash::new(b);
+        assert!(diff.dist() > 0, "{} and {}", a, b);
+        assert_eq!(diff.dist_consonants_only(), 0, "{} and {}", a, b);
+    }
+
+    // Consonants still count, both initial and trailing, also when the trailing phones of a long
+    // word overflow into the vowel bit of the first byte.
+    for &(a, b) in &[("ellen", "ellek"), ("kat", "gat"), ("jumbo", "jumpo"), ("allen", "kallen"), ("crnjolztuhlkbkm", "drnjolztuhlkbkm")] {
+        let diff = Hash::new(a) - Hash::new(b);
+        assert!(diff.dist_consonants_only() > 0, "{} and {}", a, b);
+        assert!(diff.dist_consonants_only() <= diff.dist(), "{} and {}", a, b);
+    }
+}
+
+#[test]
+fn test_configured_hasher() {
+    let configs = vec![
+        HashConfig::new(),
+        HashConfig::new().vowel_length(true),
+        HashConfig::new().soft(true),
+        HashConfig::new().split_camel_case(true).fold_after(5),
+        HashConfig::new().lang(Lang::English),
+        HashConfig::new().lang(Lang::CentralEuropean).strict(true),
+        HashConfig::new().tables(raw::TableBuilder::new().confident("lrxzqk").build()),
+    ];
+
+    let words = random_words(300);
+    for config in configs {
+        let hasher = config.clone().into_hasher();
+        assert_eq!(hasher.config(), &config);
+        for word in words.iter().map(|x| &**x).chain(vec!["", "KnightRider", "Szabó", "tuuli"]) {
+            assert_eq!(hasher.hash(word), config.build_hash(word), "{:?}: {}", config, word);
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "weights-graduated")]
+fn test_matches_pattern() {
+    use search::matches_pattern;
+
+    assert!(matches_pattern("comp*", "computer", 0));
+    // The first letter of the head is tolerated.
+    assert!(matches_pattern("comp*", "kompewter", weights::SIMILAR));
+    assert!(matches_pattern("kom*", "computer", weights::SIMILAR));
+    assert!(!matches_pattern("comp*", "kompewter", 9));
+    assert!(!matches_pattern("comp*", "jumper", 9));
+    assert!(matches_pattern("*ter", "computer", 0));
+    assert!(matches_pattern("c*ter", "computer", 0));
+    assert!(matches_pattern("c*tur", "computer", 15));
+    assert!(!matches_pattern("c*ver", "computer", 15));
+
+    // The segments must not overlap.
+    assert!(matches_pattern("jum*bo", "jumbo", 0));
+    assert!(!matches_pattern("jumb*mbo", "jumbo", 0));
+
+    // Without wildcard, the whole word is matched.
+    assert!(matches_pattern("jumpo", "jumbo", 15));
+    assert!(!matches_pattern("jumbo", "jumpers", 15));
+    assert!(matches_pattern("jumbo*", "jumpers", 15));
+    assert!(matches_pattern("*", "", 0));
+    assert!(matches_pattern("*", "anything", 0));
+
+    // More than one wildcard matches nothing.
+    assert!(!matches_pattern("c*m*r", "computer", 15));
+    assert!(!matches_pattern("**", "", 0));
+}
+
+#[test]
+fn test_best_distance() {
+    use search::best_distance;
+
+    for &(a, b) in &[("Müller", "muller"), ("Ñandu", "nandu"), ("Łódź", "lodz")] {
+        assert!((Hash::new(a) - Hash::new(b)).dist() > 0, "{} and {}", a, b);
+        assert_eq!(best_distance(a, b), 0, "{} and {}", a, b);
+    }
+
+    // The stripping never increases the distance.
+    let words = random_words(200);
+    for pair in words.chunks(2) {
+        let (a, b) = (&*pair[0], &*pair[1]);
+        assert!(best_distance(a, b) <= (Hash::new(a) - Hash::new(b)).dist());
+        assert!(best_distance(a, b) <= (Hash::new_normalized(a) - Hash::new_normalized(b)).dist());
+    }
+    assert_eq!(best_distance("jumbo", "jumpo"), (Hash::new("jumbo") - Hash::new("jumpo")).dist());
+    assert_eq!(best_distance("N\u{303}andu", "nandu"), 0);
+    assert_eq!(Hash::new_normalized("Mu\u{308}ller"), Hash::new_normalized("Müller"));
+}
+
+#[test]
+fn test_shape() {
+    use raw::{shape, Shape};
+
+    assert_eq!(shape("banana"), Shape::Nasal);
+    assert_eq!(shape("fizzes"), Shape::Fricative);
+    assert_eq!(shape("lorry"), Shape::Liquid);
+    assert_eq!(shape("bigdeck"), Shape::Plosive);
+    assert_eq!(shape("aioli"), Shape::VowelHeavy);
+    assert_eq!(shape("eau"), Shape::VowelHeavy);
+
+    // No property dominates.
+    assert_eq!(shape("mrs"), Shape::Mixed);
+    assert_eq!(shape("lamp"), Shape::Mixed);
+    assert_eq!(shape(""), Shape::Mixed);
+    assert_eq!(shape("--"), Shape::Mixed);
+}
+
+#[test]
+#[cfg(feature = "weights-graduated")]
+fn test_find_phonetic() {
+    use search::find_phonetic;
+
+    let text = "Ze computer zaid: hello, Meyer!";
+    assert_eq!(find_phonetic("kompjuter", text, 0), Vec::<usize>::new());
+    assert_eq!(find_phonetic("maier", text, 0), [25]);
+    assert_eq!(&text[25..30], "Meyer");
+    assert_eq!(find_phonetic("computor", text, 15), [3]);
+    assert_eq!(find_phonetic("hellou", text, 15), [18]);
+
+    // Non-ASCII characters are stepped over.
+    assert_eq!(find_phonetic("maier", "Zoë Meyer", 0), [5]);
+    // Short texts are compared as a whole.
+    assert_eq!(find_phonetic("jumbo", "jum", 15), [0]);
+    assert_eq!(find_phonetic("jumbo", "kit", 15), Vec::<usize>::new());
+    assert_eq!(find_phonetic("jumbo", "jumbo", 15), [0]);
+    assert_eq!(find_phonetic("", "jumbo", 15), Vec::<usize>::new());
+    assert_eq!(find_phonetic("jumbo", "", 15), Vec::<usize>::new());
+}
+
+#[test]
+fn test_unpack() {
+    // The injective phone of c, then the phones of p, e, and r (o, m, u, and t repeat the
+    // discriminant of the previous phone).
+    assert_eq!(Hash::new("computer").unpack(), [0b00000110, 0, 0, 0, 0, 0b01001001, 0, 0b10100001]);
+    assert_eq!(Hash::new("").unpack(), [0; 8]);
+    assert_eq!(Hash::empty().unpack(), [0xFF; 8]);
+
+    for word in random_words(100) {
+        let hash = Hash::new(&word);
+        assert_eq!(u64::from_be_bytes(hash.unpack()), u64::from(hash));
+    }
+}
+
+#[cfg(feature = "keyboard")]
+#[test]
+fn test_keyboard() {
+    use keyboard::{combined_typo_distance, is_adjacent, typo_distance};
+
+    assert!(is_adjacent('q', 'w') && is_adjacent('q', 'a') && is_adjacent('b', 'g') && is_adjacent('B', 'h'));
+    assert!(!is_adjacent('q', 'e') && !is_adjacent('q', 's') && !is_adjacent('q', 'z') && !is_adjacent('a', 'a'));
+    assert!(!is_adjacent('p', '[') && !is_adjacent('1', 'q'));
+
+    assert_eq!(typo_distance("", ""), 0.0);
+    assert_eq!(typo_distance("jumbo", ""), 5.0);
+    assert_eq!(typo_distance("Hello", "hrllo"), 0.5);
+    assert_eq!(typo_distance("hello", "helo"), 1.0);
+    assert_eq!(typo_distance("hello", "hlelo"), 2.0);
+
+    // Keyboard slips score closer than under the phonetic distance alone.
+    for &(a, b) in &[("gate", "fate"), ("night", "bight"), ("computer", "xomputer")] {
+        let phonetic = combined_typo_distance(a, b, 1.0);
+        assert_eq!(phonetic, 1.0 - (Hash::new(a) - Hash::new(b)).normalized());
+        assert!(combined_typo_distance(a, b, 0.5) < phonetic, "{} and {}", a, b);
+        assert!(combined_typo_distance(a, b, 0.0) < combined_typo_distance(a, "zzzzz", 0.0));
+    }
+    // The keyboard breaks phonetic ties.
+    assert_eq!((Hash::new("hello") - Hash::new("jello")).dist(), (Hash::new("hello") - Hash::new("cello")).dist());
+    assert!(combined_typo_distance("hello", "jello", 0.5) < combined_typo_distance("hello", "cello", 0.5));
+    assert_eq!(combined_typo_distance("", "", 0.5), 0.0);
+}
+
+#[test]
+#[cfg(feature = "weights-graduated")]
+fn test_suggest_weighted() {
+    use search::suggest_weighted;
+
+    // "jumpo" is closer to "jumpa", but "jumbo" is far more common.
+    assert!((Hash::new("jumpa") - Hash::new("jumpo")).dist() < (Hash::new("jumpa") - Hash::new("jumbo")).dist());
+    let dict = [("jumpo", 2), ("jumbo", 50000), ("horse", 1000000), ("maier", 1)];
+    let suggestions = suggest_weighted("jumpa", &dict, 10);
+    assert_eq!(suggestions.iter().map(|x| x.0).collect::<Vec<_>>(), ["jumbo", "jumpo", "horse", "maier"]);
+    assert!(suggestions.windows(2).all(|x| x[0].1 >= x[1].1));
+
+    // Equal frequencies give the phonetic ranking.
+    let dict = [("horse", 7), ("jumbo", 7), ("jumpo", 7)];
+    let suggestions = suggest_weighted("jumpa", &dict, 2);
+    assert_eq!(suggestions.iter().map(|x| x.0).collect::<Vec<_>>(), ["jumpo", "jumbo"]);
+    assert_eq!(suggestions[0].1, (Hash::new("jumpa") - Hash::new("jumpo")).normalized() + 0.05);
+
+    assert_eq!(suggest_weighted("jumbo", &[("jumbo", 0)], 5), [("jumbo", 1.0)]);
+    assert!(suggest_weighted("jumbo", &[], 5).is_empty());
+    assert!(suggest_weighted("jumbo", &dict, 0).is_empty());
+}
+
+#[test]
+fn test_is_degenerate() {
+    // Every phone of "hello" repeats the discriminant of the previous one.
+    for word in &["", "a", "I", "oo", "hi", "aeiou", "B", "!!", "hello"] {
+        assert!(Hash::new(word).is_degenerate(), "{}", word);
+    }
+    for word in &["cat", "computer", "at", "ok", "jumbo", "help"] {
+        assert!(!Hash::new(word).is_degenerate(), "{}", word);
+    }
+}
+
+#[test]
+fn test_confusability() {
+    use stats::confusability;
+
+    let dict: Vec<Hash> = ["jumpo", "jumbo", "horse", "norse", "maier", "jumbu"].iter().map(|x| Hash::new(x)).collect();
+    let report = confusability("jumbo", &dict);
+    let similar = dict.iter().filter(|&&x| (Hash::new("jumbo") - x).similar()).count();
+    assert_eq!(report.similar, similar);
+    // "jumbo" itself, "jumpo" at 13, and "jumbu" at 9.
+    #[cfg(feature = "weights-graduated")]
+    assert_eq!(report.similar, 3);
+    assert_eq!(report.nearest, Some(0));
+    let mean = dict.iter().map(|&x| (Hash::new("jumbo") - x).dist()).sum::<u32>() as f64 / 6.0;
+    assert_eq!(report.mean, mean);
+
+    let report = confusability("horse", &dict[..1]);
+    #[cfg(feature = "weights-graduated")]
+    assert_eq!(report.similar, 0);
+    assert_eq!(report.nearest, Some((Hash::new("horse") - Hash::new("jumpo")).dist()));
+
+    let report = confusability("jumbo", &[]);
+    assert_eq!((report.similar, report.nearest, report.mean), (0, None, 0.0));
+}
+
+#[test]
+#[cfg(feature = "weights-graduated")]
+fn test_index_merge() {
+    use index::PhoneticIndex;
+
+    let words = random_words(400);
+    let (left, right) = words.split_at(250);
+    let mut index = PhoneticIndex::from_words(left);
+    let mut shard = PhoneticIndex::from_words(right);
+    // Overlapping spellings are kept once.
+    shard.insert(&left[0]);
+    index.merge(shard);
+
+    let whole = PhoneticIndex::from_words(&words);
+    assert_eq!(index.len(), whole.len());
+    for word in &words {
+        let mut merged = index.get(word).to_vec();
+        let mut expected = whole.get(word).to_vec();
+        merged.sort();
+        expected.sort();
+        assert_eq!(merged, expected);
+    }
+
+    let mut index = PhoneticIndex::from_words(&["jumbo", "maier"]);
+    index.merge(PhoneticIndex::from_words(&["jumpo", "meyer", "computer"]));
+    assert_eq!(index.lookup("jumbo", 15), ["jumbo", "jumpo"]);
+    assert_eq!(index.lookup("meier", 10), ["maier", "meyer"]);
+    assert_eq!(index.get("computer"), ["computer"]);
+    index.merge(PhoneticIndex::new());
+    assert_eq!(index.len(), 5);
+}
+
+#[test]
+#[cfg(feature = "weights-graduated")]
+fn test_exclusion_matcher() {
+    use search::ExclusionMatcher;
+
+    let matcher = ExclusionMatcher::from_pairs(&[("Celebrex", "Celexa"), ("jumbo", "jumpo")]);
+    assert!((Hash::new("celebrex") - Hash::new("celexa")).similar());
+    assert!(!matcher.similar("Celebrex", "Celexa"));
+    assert!(!matcher.similar("celexa", "CELEBREX"));
+    assert!(!matcher.similar("jumpo", "jumbo"));
+    assert!(matcher.is_excluded("JUMPO", "jumbo"));
+
+    // Other pairs behave normally.
+    assert!(matcher.similar("maier", "meyer"));
+    assert!(matcher.similar("jumbo", "jumbu"));
+    assert!(matcher.similar("jumbo", "jumbo"));
+    assert!(!matcher.similar("jumbo", "horse"));
+    assert!(!matcher.is_excluded("jumbo", "horse"));
+    assert!(ExclusionMatcher::new().similar("jumbo", "jumpo"));
+}
+
+#[test]
+fn test_min_distance_to_aliases() {
+    use search::min_distance_to_aliases;
+
+    let aliases = ["Bob", "Robert", "Bobby", "Rob"];
+    assert_eq!(min_distance_to_aliases("robbert", &aliases), 0);
+    for query in &["roberta", "rupert", "bop", "jumbo"] {
+        let min = aliases.iter().map(|x| (Hash::new(query) - Hash::new(x)).dist()).min();
+        assert_eq!(Some(min_distance_to_aliases(query, &aliases)), min);
+    }
+    assert_eq!(min_distance_to_aliases("jumbo", &[]), u32::MAX);
+}
+
+#[test]
+fn test_phonetic_key() {
+    use index::PhoneticKey;
+    use std::collections::HashMap;
+
+    let words = ["maier", "meyer", "mayr", "jumbo", "jumpo", "jumbu", "horse"];
+    let group = |coarseness| {
+        let mut groups: HashMap<PhoneticKey, Vec<&str>> = HashMap::new();
+        for word in &words {
+            groups.entry(PhoneticKey::from_word(word, coarseness)).or_default().push(word);
+        }
+        let mut groups: Vec<Vec<&str>> = groups.into_iter().map(|x| x.1).collect();
+        groups.sort();
+        groups
+    };
+
+    assert_eq!(group(0), [vec!["horse"], vec!["jumbo"], vec!["jumbu"], vec!["jumpo"], vec!["maier", "meyer"], vec!["mayr"]]);
+    assert_eq!(group(56), [vec!["horse"], vec!["jumbo", "jumpo", "jumbu"], vec!["maier", "meyer", "mayr"]]);
+    assert_eq!(group(64), [words.to_vec()]);
+
+    // The coarseness is part of the key.
+    let hash = Hash::new("a");
+    assert!(PhoneticKey::from_hash(hash, 8) != PhoneticKey::from_hash(hash, 16));
+    assert_eq!(PhoneticKey::from_hash(hash, 8), PhoneticKey::from_word("a", 8));
+}
+
+#[test]
+fn test_phone_diff() {
+    use raw::phone_diff;
+
+    let phone = |x: &str| raw::phones(x).next().unwrap();
+
+    // One consonant apart.
+    assert_eq!(phone_diff("jumbo", "jumpo"), (vec![phone("b")], vec![phone("p")]));
+    assert_eq!(phone_diff("cat", "cap"), (vec![phone("t")], vec![phone("p")]));
+    // Only present in one of them.
+    assert_eq!(phone_diff("cart", "cat"), (vec![phone("r")], vec![]));
+    // Order and repetitions do not matter.
+    assert_eq!(phone_diff("listen", "silent"), (vec![], vec![]));
+    assert_eq!(phone_diff("aab", "ba"), (vec![], vec![]));
+
+    let (x, y) = phone_diff("fizzes", "lorry");
+    assert!(x.windows(2).all(|x| x[0] < x[1]) && y.windows(2).all(|x| x[0] < x[1]));
+    assert!(x.iter().all(|phone| !y.contains(phone)));
+}
+
+#[test]
+fn test_silent_h() {
+    let silent = HashConfig::new().silent_h(true);
+    let aspirate = HashConfig::new().silent_h(false);
+
+    assert_eq!(silent.build_hash("hour"), silent.build_hash("our"));
+    assert_eq!(silent.build_hash("House"), silent.build_hash("ouse"));
+    assert_eq!(silent.build_hash("honest"), Hash::new("onest"));
+    assert_eq!(silent.build_hash("Rhythm"), Hash::new("rytm"));
+    assert!(aspirate.build_hash("hour") != aspirate.build_hash("our"));
+    assert!(aspirate.build_hash("house") != aspirate.build_hash("ouse"));
+    assert_eq!(aspirate.build_hash("house"), Hash::new("house"));
+
+    // It applies after the language mode.
+    let config = HashConfig::new().lang(Lang::CentralEuropean).silent_h(true);
+    assert_eq!(config.build_hash("Szechenyi"), HashConfig::new().lang(Lang::CentralEuropean).build_hash("secenyi"));
+    assert_eq!(silent.into_hasher().hash("hour"), Hash::new("our"));
+}
+
+#[test]
+fn test_mixed() {
+    use std::collections::HashSet;
+
+    let hashes: HashSet<Hash> = random_words(5000).iter().map(|x| Hash::new(x)).collect();
+
+    // The mix is injective.
+    let mixed: HashSet<u64> = hashes.iter().map(|x| x.mixed()).collect();
+    assert_eq!(mixed.len(), hashes.len());
+
+    // The low byte of the raw hashes is the last phone, whereas the mixed low bytes are spread
+    // over all of the buckets.
+    let buckets = |values: &mut dyn Iterator<Item = u64>| {
+        let mut counts = [0usize; 256];
+        for x in values {
+            counts[(x & 0xFF) as usize] += 1;
+        }
+        (counts.iter().filter(|&&x| x > 0).count(), *counts.iter().max().unwrap())
+    };
+    let (raw_used, raw_max) = buckets(&mut hashes.iter().map(|&x| u64::from(x)));
+    let (mixed_used, mixed_max) = buckets(&mut mixed.iter().cloned());
+    assert!(raw_used <= 32);
+    assert_eq!(mixed_used, 256);
+    assert!(mixed_max * 10 < raw_max);
+}
+
+#[test]
+fn test_phone_edit_distance() {
+    use raw::phone_edit_distance;
+
+    // Insertions and deletions cost only the phones involved.
+    assert_eq!(phone_edit_distance("schmid", "schmidt"), 4);
+    assert_eq!(phone_edit_distance("schmidt", "schmid"), 4);
+    assert_eq!(phone_edit_distance("color", "colour"), 4);
+    assert_eq!(phone_edit_distance("ship", "shipping"), 16);
+    assert_eq!(phone_edit_distance("", "abc"), 12);
+    assert!(phone_edit_distance("schmid", "schmidt") < (Hash::new("schmid") - Hash::new("schmidt")).dist());
+
+    // Substitutions cost the differing bits.
+    assert_eq!(phone_edit_distance("jumbo", "jumpo"), 1);
+    assert_eq!(phone_edit_distance("Jumbo", "jumbo"), 0);
+    assert_eq!(phone_edit_distance("a-b", "ab"), 0);
+
+    // It is a metric.
+    let words = random_words(30);
+    for a in &words {
+        assert_eq!(phone_edit_distance(a, a), 0);
+        for b in &words {
+            assert_eq!(phone_edit_distance(a, b), phone_edit_distance(b, a));
+            for c in words.iter().take(5) {
+                assert!(phone_edit_distance(a, c) <= phone_edit_distance(a, b) + phone_edit_distance(b, c));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "tables")]
+#[test]
+fn test_dump_tables() {
+    let tables = raw::dump_tables();
+
+    // Parse the CSV back.
+    let csv = tables.to_csv();
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("letter,phone,injective_phone"));
+    let rows: Vec<(char, u8, u8)> = lines
+        .map(|line| {
+            let fields: Vec<&str> = line.split(',').collect();
+            assert_eq!(fields.len(), 3);
+            (fields[0].parse().unwrap(), fields[1].parse().unwrap(), fields[2].parse().unwrap())
+        })
+        .collect();
+    assert!(raw::tables_consistent(26, rows.len() - 26, 26, rows.len() - 26));
+
+    let mut parsed = tables.clone();
+    for (i, &(letter, phone, injective)) in rows.iter().enumerate() {
+        if i < 26 {
+            assert_eq!(letter, (b'a' + i as u8) as char);
+            parsed.phones[i] = phone;
+            parsed.injective_phones[i] = injective;
+        } else {
+            assert_eq!(letter as u32, 0xDF + i as u32 - 26);
+            parsed.phones_c1[i - 26] = phone;
+            parsed.injective_phones_c1[i - 26] = injective;
+        }
+    }
+    assert_eq!(parsed, tables);
+
+    // The tables are the ones hashed with.
+    for (i, x) in (b'a'..=b'z').enumerate() {
+        assert_eq!(raw::phone(x), Some(tables.phones[i]));
+        assert_eq!(raw::map_first(x), tables.injective_phones[i]);
+        assert_eq!(raw::injective_phone(x), Some(tables.injective_phones[i]));
+    }
+    assert_eq!(tables.vowel_length, raw::VOWEL_LENGTH);
+}
+
+#[test]
+fn test_all_metrics() {
+    let words = random_words(200);
+    for pair in words.chunks(2).chain(vec![&["jumbo".to_owned(), "jumpo".to_owned()][..]]) {
+        let diff = Hash::new(&pair[0]) - Hash::new(&pair[1]);
+        let metrics = diff.all_metrics();
+        assert_eq!(metrics.dist, diff.dist());
+        assert_eq!(metrics.hamming, diff.hamming());
+        assert_eq!(metrics.xor, diff.xor());
+        assert_eq!(metrics.normalized, diff.normalized());
+    }
+
+    let metrics = (Hash::new("jumbo") - Hash::new("jumbo")).all_metrics();
+    assert_eq!(metrics, Metrics { dist: 0, hamming: 0, xor: 0, normalized: 1.0 });
+}
+
+#[test]
+fn test_centroid() {
+    let cluster: Vec<_> = ["smith", "smyth", "smitt", "smit", "schmidt"]
+        .iter()
+        .map(|x| Hash::new(x))
+        .collect();
+    let centroid = Hash::centroid(&cluster);
+    for &x in &cluster {
+        assert!((centroid - x).similar());
+    }
+
+    // The centroid minimizes the summed Hamming distance.
+    let sum = |center: Hash| cluster.iter().map(|&x| (center - x).hamming()).sum::<u32>();
+    for &x in &cluster {
+        assert!(sum(centroid) <= sum(x));
+    }
+
+    let words = ["jumbo", "jumpo", "jumbo", "horse"];
+    let hashes: Vec<_> = words.iter().map(|x| Hash::new(x)).collect();
+    assert_eq!(Hash::centroid(&hashes), Hash::consensus(&words));
+    assert_eq!(Hash::centroid(&[]).hash, 0);
+}
+
+#[test]
+fn test_edit_impact() {
+    use stats::edit_impact;
+
+    // Changing the class of a consonant matters more than changing a vowel.
+    assert!(edit_impact("peter", 2, 'f') > edit_impact("peter", 3, 'a'));
+    assert!(edit_impact("baker", 2, 'g') > edit_impact("baker", 3, 'a'));
+    assert!(edit_impact("smith", 1, 'r') > edit_impact("smith", 2, 'y'));
+
+    assert_eq!(edit_impact("jumbo", 3, 'b'), 0);
+    assert_eq!(edit_impact("jumbo", 3, 'p'), (Hash::new("jumbo") - Hash::new("jumpo")).dist());
+    assert_eq!(edit_impact("Szabó", 4, 'o'), (Hash::new("Szabó") - Hash::new("Szabo")).dist());
+}
+
+#[test]
+fn test_dist_with_length() {
+    let dist = |a: &str, b: &str| {
+        let (a, len_a) = Hash::new_with_len(a);
+        let (b, len_b) = Hash::new_with_len(b);
+        (a - b).dist_with_length(len_a, len_b, 4)
+    };
+
+    // Length mismatched pairs.
+    assert!(dist("comp", "computer") > (Hash::new("comp") - Hash::new("computer")).dist());
+    #[cfg(feature = "weights-graduated")]
+    assert!((Hash::new("comp") - Hash::new("computer")).similar() && dist("comp", "computer") >= 15);
+    #[cfg(feature = "weights-graduated")]
+    assert!(dist("me", "awesome") >= 15);
+    assert!(dist("tor", "torrent") > (Hash::new("tor") - Hash::new("torrent")).dist());
+
+    // Pairs of the same length are unaffected.
+    assert_eq!(dist("maier", "meyer"), 0);
+    assert_eq!(dist("smith", "smyth"), 0);
+    assert_eq!(dist("lizzard", "wizzard"), (Hash::new("lizzard") - Hash::new("wizzard")).dist());
+
+    for word in random_words(50) {
+        let (hash, len) = Hash::new_with_len(&word);
+        assert_eq!(hash, Hash::new(&word));
+        assert!(len as usize <= word.len());
+    }
+    assert_eq!(Hash::new_with_len(&"pa".repeat(200)).1, 255);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_config() {
+    let config: HashConfig = serde_json::from_str(
+        r#"{ "lang": "CentralEuropean", "split_camel_case": true, "fold_after": 6 }"#,
+    )
+    .unwrap();
+    let expected = HashConfig::new().lang(Lang::CentralEuropean).split_camel_case(true).fold_after(6);
+    assert_eq!(config, expected);
+    assert_eq!(config.build_hash("SzabóKovács"), expected.build_hash("SzabóKovács"));
+    assert_eq!(config.build_hash("Szabó"), config.build_hash("Sabo"));
+
+    // Round trip, including the phone tables.
+    let tables = raw::TableBuilder::new().confident("lrk").build();
+    let config = HashConfig::new().vowel_length(true).tables(tables);
+    let json = serde_json::to_string(&config).unwrap();
+    assert_eq!(serde_json::from_str::<HashConfig>(&json).unwrap(), config);
+    assert_eq!(serde_json::from_str::<HashConfig>("{}").unwrap(), HashConfig::new());
+
+    // Invalid profiles.
+    assert!(serde_json::from_str::<HashConfig>(r#"{ "fold_after": 0 }"#).is_err());
+    assert!(serde_json::from_str::<HashConfig>(r#"{ "fold_after": 9 }"#).is_err());
+    assert!(serde_json::from_str::<HashConfig>(r#"{ "lang": "Klingon" }"#).is_err());
+    assert!(serde_json::from_str::<HashConfig>(r#"{ "treshold": 15 }"#).is_err());
+    let long = json.replacen("[", "[0,", 1);
+    assert!(serde_json::from_str::<HashConfig>(&long).is_err());
+}
+
+#[cfg(all(feature = "tables", feature = "serde"))]
+#[test]
+fn test_serde_tables() {
+    let tables = raw::dump_tables();
+    let json = serde_json::to_string(&tables).unwrap();
+    assert_eq!(serde_json::from_str::<raw::TablesData>(&json).unwrap(), tables);
+
+    // The C1 tables must have a phone of every letter.
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value["phones_c1"].as_array_mut().unwrap().pop();
+    assert!(serde_json::from_value::<raw::TablesData>(value.clone()).is_err());
+    value["phones_c1"].as_array_mut().unwrap().extend(vec![0.into(), 0.into()]);
+    assert!(serde_json::from_value::<raw::TablesData>(value).is_err());
+}
+
+#[test]
+fn test_coverage_radius() {
+    use stats::{coverage_radius, min_bits_to_exceed};
+
+    // The extreme differences are just on either side of the threshold.
+    let diff = |xor: u64| {
+        Hash::from_u32_pair(0, 0) - Hash::from_u32_pair((xor >> 32) as u32, xor as u32)
+    };
+    let radius = coverage_radius(weights::SIMILAR);
+    assert!(diff((1 << radius) - 1).similar());
+    assert!(!diff((1 << (radius + 1)) - 1).similar());
+    assert!(!diff(!0 << (64 - min_bits_to_exceed(weights::SIMILAR).unwrap())).similar());
+    #[cfg(feature = "weights-graduated")]
+    assert_eq!((coverage_radius(15), min_bits_to_exceed(15)), (11, Some(1)));
+
+    assert_eq!(coverage_radius(0), 0);
+    assert_eq!(coverage_radius(1), 0);
+    assert_eq!(coverage_radius(2), 1);
+    assert_eq!(coverage_radius(u32::MAX), 64);
+    assert_eq!(min_bits_to_exceed(0), Some(0));
+    assert_eq!(min_bits_to_exceed(weights::DEFAULT[7] + 1), Some(2));
+    assert_eq!(min_bits_to_exceed(weights::MAX_DIST), Some(64));
+    assert_eq!(min_bits_to_exceed(weights::MAX_DIST + 1), None);
+}
+
+#[test]
+fn test_classify_into() {
+    use search::classify_into;
+
+    let buckets = [
+        ("jumbo", Hash::new("jumbo")),
+        ("meyer", Hash::new("meyer")),
+        ("smith", Hash::centroid(&[Hash::new("smith"), Hash::new("smyth"), Hash::new("schmidt")])),
+    ];
+    assert_eq!(classify_into("jumpo", &buckets), "jumbo");
+    assert_eq!(classify_into("maier", &buckets), "meyer");
+    assert_eq!(classify_into("mayer", &buckets), "meyer");
+    assert_eq!(classify_into("smit", &buckets), "smith");
+    assert_eq!(classify_into("schmitt", &buckets), "smith");
+
+    // Ties favor the first bucket.
+    let tied = [("first", Hash::new("maier")), ("second", Hash::new("meyer"))];
+    assert_eq!(classify_into("mayer", &tied), "first");
+    assert_eq!(classify_into("horse", &tied[1..]), "second");
+}
+
+#[test]
+fn test_lazy_distance_matrix() {
+    use search::{distance_matrix, LazyDistanceMatrix};
+
+    let words = random_words(30);
+    let words: Vec<&str> = words.iter().map(|x| &x[..]).collect();
+    let full = distance_matrix(&words);
+    let mut matrix = LazyDistanceMatrix::new(&words);
+    assert_eq!(matrix.len(), words.len());
+    assert_eq!(matrix.cached(), 0);
+
+    for (i, row) in full.iter().enumerate() {
+        for (j, &dist) in row.iter().enumerate() {
+            assert_eq!(matrix.get(i, j), dist);
+            assert_eq!(matrix.get(i, j), matrix.get(j, i));
+        }
+    }
+    assert_eq!(matrix.cached(), words.len() * (words.len() - 1) / 2);
+
+    let mut matrix = LazyDistanceMatrix::new(&["jumbo", "horse", "jumpo"]);
+    assert_eq!(matrix.get(1, 1), 0);
+    assert_eq!(matrix.cached(), 0);
+    let dist = (Hash::new("jumpo") - Hash::new("jumbo")).dist();
+    assert_eq!(matrix.get(2, 0), dist);
+    assert_eq!(matrix.get(0, 2), dist);
+    assert_eq!(matrix.cached(), 1);
+    assert!(LazyDistanceMatrix::new(&[]).is_empty());
+}
+
+#[test]
+fn test_document_similarity() {
+    use phrase::{document_signature, document_similarity};
+
+    let original = "The quick brown fox jumps over the lazy dog and meets Mr. Meyer.";
+    let misspelled = "Teh qwick brownn foks jumpps ovver the lazzy dogg and meats Mr Maier";
+    let unrelated = "Colorless green ideas sleep furiously in a tin can.";
+    assert!(document_similarity(original, misspelled) > 0.8);
+    assert!(document_similarity(original, unrelated) < 0.2);
+    assert!(document_similarity(original, misspelled) > document_similarity(original, unrelated));
+
+    assert_eq!(document_similarity(original, original), 1.0);
+    assert_eq!(document_similarity("", "--"), 1.0);
+    assert_eq!(document_similarity("jumbo", ""), 0.0);
+    assert_eq!(document_signature("jumbo jumbo, JUMBO!").len(), 1);
+}
+
+#[test]
+fn test_first_letter_candidates() {
+    // The first letters are recovered.
+    for &word in &["jumbo", "Horse", "zebra", "lizzard", "banana", "xavier"] {
+        let first = word.chars().next().unwrap().to_ascii_lowercase();
+        assert_eq!(Hash::new(word).first_letter_candidates(), [first]);
+    }
+    assert_eq!(Hash::new("quantum").first_letter_candidates(), ['q', 'ç']);
+    for x in b'a'..=b'z' {
+        let candidates = Hash::new(&(x as char).to_string()).first_letter_candidates();
+        assert!(candidates.contains(&(x as char)));
+        // The ASCII letters have distinct phones.
+        assert!(candidates.iter().all(|&y| y == x as char || !y.is_ascii()));
+    }
+
+    // Every letter of ASCII and the Latin-1 Supplement is the candidate of a single first byte,
+    // namely its phone.
+    let mut letters: Vec<char> = (0..=255)
+        .flat_map(|x| Hash::from_u32_pair(x << 24, 0).first_letter_candidates())
+        .collect();
+    assert_eq!(letters.len(), 26 + 32);
+    letters.sort();
+    letters.dedup();
+    assert_eq!(letters.len(), 26 + 32);
+    assert!(!letters.contains(&'÷'));
+
+    // Letters outside of ASCII and non-letters have the first byte of w.
+    assert_eq!(Hash::new("Émile").first_letter_candidates(), ['w']);
+    assert_eq!(Hash::new("").first_letter_candidates(), ['w']);
+}
+
+#[test]
+fn test_within() {
+    let words = random_words(300);
+    let hashes: Vec<Hash> = words.iter().map(|x| Hash::new(x)).collect();
+    for pair in hashes.windows(2) {
+        let diff = pair[0] - pair[1];
+        for &threshold in &[0, 1, 14, 15, 50, 200, weights::MAX_DIST] {
+            let expected = if diff.dist() <= threshold { Some(diff.dist()) } else { None };
+            assert_eq!(diff.within(threshold), expected);
+        }
+    }
+
+    let diff = Hash::new("jumbo") - Hash::new("jumpo");
+    assert_eq!(diff.within(diff.dist()), Some(diff.dist()));
+    assert_eq!(diff.within(diff.dist() - 1), None);
+    assert_eq!((Hash::new("maier") - Hash::new("meyer")).within(0), Some(0));
+}
+
+#[test]
+fn test_document_similarity_filtered() {
+    use phrase::{
+        document_signature, document_signature_filtered, document_similarity,
+        document_similarity_filtered,
+    };
+    use std::collections::HashSet;
+
+    let stop_words: HashSet<&str> =
+        ["a", "an", "and", "of", "the", "to", "in", "on"].iter().cloned().collect();
+
+    // The documents differ only in function words.
+    let a = "The history of the Roman Empire, and the fall of Rome";
+    let b = "A history on Roman Empire to a fall in Rome";
+    let unfiltered = document_similarity(a, b);
+    let filtered = document_similarity_filtered(a, b, &stop_words);
+    assert!(filtered > unfiltered);
+    assert_eq!(filtered, 1.0);
+
+    // Stop words are matched case insensitively, and nothing else is skipped.
+    assert_eq!(
+        document_signature_filtered("THE jumbo OF horse", &stop_words),
+        document_signature("jumbo horse")
+    );
+    let none = HashSet::new();
+    assert_eq!(document_signature_filtered("jumbo horse", &none), document_signature("jumbo horse"));
+    assert_eq!(document_similarity_filtered("the", "of a", &stop_words), 1.0);
+}
+
+#[test]
+fn test_corpus_diversity() {
+    use stats::corpus_diversity;
+
+    let homophones = ["maier", "meyer", "mayer", "smith", "smyth", "jumbo", "jumba", "dog", "dogg"];
+    let diverse = ["jumbo", "horse", "lizzard", "computer", "maier", "smith", "kitten", "dog"];
+    assert!(corpus_diversity(&homophones) < 0.5);
+    assert_eq!(corpus_diversity(&diverse), 1.0);
+    assert!(corpus_diversity(&homophones) < corpus_diversity(&diverse));
+
+    assert_eq!(corpus_diversity(&["jumbo", "jumbo"]), 0.5);
+    assert_eq!(corpus_diversity(&[]), 1.0);
+}
+
+#[test]
+#[cfg(feature = "weights-graduated")]
+fn test_similarity_predicate() {
+    use search::{neighbors, neighbors_by, ClassAtMost, MaxDist, SameInitial, SimilarityPredicate};
+
+    let words = ["jumbo", "gumbo", "kat", "gat", "maier", "meyer", "horse"];
+    let dictionary: Vec<(Hash, &str)> = words.iter().map(|&x| (Hash::new(x), x)).collect();
+
+    // A custom rule: similar, but only if the last phones agree.
+    struct SameEnding;
+    impl SimilarityPredicate for SameEnding {
+        fn is_similar(&self, diff: Difference) -> bool {
+            diff.similar() && diff.xor() & 0xFF == 0
+        }
+    }
+    assert_eq!(neighbors_by("jumpo", &SameEnding, &dictionary), Vec::<&str>::new());
+    assert_eq!(neighbors_by("mayer", &SameEnding, &dictionary), ["maier", "meyer"]);
+
+    // The built-in rules.
+    for &max_dist in &[0, 15, 100] {
+        assert_eq!(
+            neighbors_by("jumpo", &MaxDist(max_dist), &dictionary),
+            neighbors("jumpo", max_dist, &dictionary)
+        );
+    }
+    assert_eq!(neighbors_by("jumpo", &SameInitial(15), &dictionary), ["jumbo"]);
+    assert_eq!(neighbors_by("kat", &ClassAtMost(Match::Homophone), &dictionary), ["kat", "gat"]);
+    assert_eq!(neighbors_by("kat", &ClassAtMost(Match::Exact), &dictionary), ["kat"]);
+    let closure = |x: Difference| x.dist() == 0;
+    assert_eq!(neighbors_by("meyer", &closure, &dictionary), ["maier", "meyer"]);
+}
+
+#[test]
+fn test_filter_table() {
+    for prev in 0..256 {
+        for x in 0..256 {
+            assert_eq!(raw::filter_lookup(prev as u8, x as u8), raw::filter(prev as u8, x as u8));
+        }
+    }
+}
+
+/// Mixed input for the filter benchmarks: ASCII letters, punctuation, digits, and UTF-8.
+#[cfg(feature = "nightly")]
+const MIXED: &str = "Schröder-Müller, 42 Straße; ça va? Ærøskøbing & Łódź (1999) jalapeño ok";
+
+#[cfg(feature = "nightly")]
+#[bench]
+fn bench_filter_lookup(b: &mut Bencher) {
+    let input = MIXED.repeat(64);
+
+    b.iter(|| {
+        let mut prev = 0;
+        for &x in test::black_box(&input).as_bytes() {
+            if let Some(phone) = raw::filter_lookup(prev, x) {
+                prev = phone;
+            }
+        }
+        prev
+    });
+}
+
+#[cfg(feature = "nightly")]
+#[bench]
+fn bench_filter(b: &mut Bencher) {
+    let input = MIXED.repeat(64);
+
+    b.iter(|| {
+        let mut prev = 0;
+        for &x in test::black_box(&input).as_bytes() {
+            if let Some(phone) = raw::filter(prev, x) {
+                prev = phone;
+            }
+        }
+        prev
+    });
+}
+
+#[cfg(feature = "nightly")]
 #[bench]
 fn bench_dict(b: &mut Bencher) {
     use std::fs;
@@ -93,3 +2509,109 @@ fn bench_dict(b: &mut Bencher) {
         vec
     });
 }
+
+/// A synthetic word of some length, alternating consonants and vowels.
+#[cfg(feature = "nightly")]
+fn synthetic_word(len: usize) -> String {
+    "kalimotorunepasibe".chars().cycle().take(len).collect()
+}
+
+/// Benchmark hashing a synthetic word of some length, counting its bytes as the throughput.
+#[cfg(feature = "nightly")]
+fn bench_len<F: Fn(&str) -> Hash>(b: &mut Bencher, len: usize, hash: F) {
+    let word = synthetic_word(len);
+    b.bytes = len as u64;
+
+    b.iter(|| hash(test::black_box(&word)));
+}
+
+// Hashing stops after eight phones, so the cost should plateau rather than grow with the length.
+
+#[cfg(feature = "nightly")]
+#[bench]
+fn bench_len_2(b: &mut Bencher) {
+    bench_len(b, 2, Hash::new);
+}
+
+#[cfg(feature = "nightly")]
+#[bench]
+fn bench_len_5(b: &mut Bencher) {
+    bench_len(b, 5, Hash::new);
+}
+
+#[cfg(feature = "nightly")]
+#[bench]
+fn bench_len_10(b: &mut Bencher) {
+    bench_len(b, 10, Hash::new);
+}
+
+#[cfg(feature = "nightly")]
+#[bench]
+fn bench_len_20(b: &mut Bencher) {
+    bench_len(b, 20, Hash::new);
+}
+
+#[cfg(feature = "nightly")]
+#[bench]
+fn bench_len_50(b: &mut Bencher) {
+    bench_len(b, 50, Hash::new);
+}
+
+// The configured hashing stops after eight phones too, unless folding, which reads the whole word.
+
+#[cfg(feature = "nightly")]
+#[bench]
+fn bench_config_len_5(b: &mut Bencher) {
+    let config = HashConfig::new();
+    bench_len(b, 5, |x| config.build_hash(x));
+}
+
+#[cfg(feature = "nightly")]
+#[bench]
+fn bench_config_len_50(b: &mut Bencher) {
+    let config = HashConfig::new();
+    bench_len(b, 50, |x| config.build_hash(x));
+}
+
+#[cfg(feature = "nightly")]
+#[bench]
+fn bench_config_fold_len_50(b: &mut Bencher) {
+    let config = HashConfig::new().fold_after(8);
+    bench_len(b, 50, |x| config.build_hash(x));
+}
+
+#[cfg(feature = "nightly")]
+#[bench]
+fn bench_phone_edit_distance(b: &mut Bencher) {
+    let words = random_words(100);
+
+    b.iter(|| {
+        let mut sum = 0;
+        for pair in words.chunks(2) {
+            sum += raw::phone_edit_distance(test::black_box(&pair[0]), test::black_box(&pair[1]));
+        }
+        sum
+    });
+}
+
+#[cfg(feature = "nightly")]
+fn bench_scan(b: &mut Bencher, dist: fn(Difference) -> Option<u32>) {
+    let hashes: Vec<Hash> = random_words(1000).iter().map(|x| Hash::new(x)).collect();
+    let query = Hash::new("jumbo");
+
+    b.iter(|| hashes.iter().filter_map(|&x| dist(test::black_box(query - x))).count());
+}
+
+// Most of the words of a scan are rejected by their first bytes, which `within` stops at.
+
+#[cfg(feature = "nightly")]
+#[bench]
+fn bench_scan_dist(b: &mut Bencher) {
+    bench_scan(b, |x| Some(x.dist()).filter(|&x| x <= 15));
+}
+
+#[cfg(feature = "nightly")]
+#[bench]
+fn bench_scan_within(b: &mut Bencher) {
+    bench_scan(b, |x| x.within(15));
+}
@@ -0,0 +1,92 @@
+//! Byte weights of the distances.
+
+/// The weights of the graduated distance.
+///
+/// The weights are indexed from the last phone to the first phone, and follow the Fibonacci
+/// sequence.
+pub(crate) const GRADUATED: [u32; 8] = [1, 2, 3, 5, 8, 13, 21, 34];
+
+/// The weights of `Difference::dist`.
+///
+/// These are the graduated weights, unless another set is selected by the `weights-flat` or
+/// `weights-front-heavy` feature.
+#[cfg(not(any(feature = "weights-flat", feature = "weights-front-heavy")))]
+pub(crate) const DEFAULT: [u32; 8] = GRADUATED;
+#[cfg(feature = "weights-flat")]
+pub(crate) const DEFAULT: [u32; 8] = WeightProfile::Flat.weights();
+#[cfg(feature = "weights-front-heavy")]
+pub(crate) const DEFAULT: [u32; 8] = WeightProfile::FrontHeavy.weights();
+
+/// The largest distance under the weights of `Difference::dist`, where every bit differs.
+pub(crate) const MAX_DIST: u32 = {
+    let mut res = 0;
+    let mut i = 0;
+    while i < 8 {
+        res += 8 * DEFAULT[i];
+        i += 1;
+    }
+
+    res
+};
+
+/// The distance, below which `Difference::similar` considers two words similar.
+pub(crate) const SIMILAR: u32 = 15;
+
+#[cfg(any(
+    all(feature = "weights-graduated", feature = "weights-flat"),
+    all(feature = "weights-graduated", feature = "weights-front-heavy"),
+    all(feature = "weights-flat", feature = "weights-front-heavy"),
+))]
+compile_error!("at most one of the `weights-*` features can be enabled");
+
+/// A named weighting of the bytes.
+///
+/// Every profile assigns a weight to each of the eight bytes of the hash, from the last phone to
+/// the first phone.
+///
+/// # Examples
+///
+/// ```rust
+/// use eudex::{Hash, WeightProfile};
+///
+/// let diff = Hash::new("lulz") - Hash::new("lol");
+/// # #[cfg(feature = "weights-graduated")]
+/// assert_eq!(diff.dist_profile(WeightProfile::Graduated), diff.dist());
+/// assert_eq!(diff.dist_profile(WeightProfile::Flat), diff.hamming());
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum WeightProfile {
+    /// The Fibonacci weights, `[1, 2, 3, 5, 8, 13, 21, 34]`, used by
+    /// [`Difference::dist`](./struct.Difference.html#method.dist) by default.
+    Graduated,
+    /// Every byte weighs 1, equivalent to [`Difference::hamming`](./struct.Difference.html#method.hamming).
+    Flat,
+    /// Powers of two, `[1, 2, 4, 8, 16, 32, 64, 128]`, emphasizing the first phones even more than
+    /// the graduated weights.
+    FrontHeavy,
+    /// The reversed graduated weights, `[34, 21, 13, 8, 5, 3, 2, 1]`, emphasizing the last phones.
+    TailHeavy,
+}
+
+impl WeightProfile {
+    /// Get the weights of the profile, from the last phone to the first phone.
+    pub const fn weights(self) -> [u32; 8] {
+        match self {
+            WeightProfile::Graduated => GRADUATED,
+            WeightProfile::Flat => [1; 8],
+            WeightProfile::FrontHeavy => [1, 2, 4, 8, 16, 32, 64, 128],
+            WeightProfile::TailHeavy => [34, 21, 13, 8, 5, 3, 2, 1],
+        }
+    }
+}
+
+/// Weigh the Hamming weight of every byte of a XOR and add them.
+#[inline]
+pub(crate) fn weighted(xor: u64, weights: &[u32; 8]) -> u32 {
+    let mut res = 0;
+    for (i, &weight) in weights.iter().enumerate() {
+        res += ((xor >> (8 * i)) as u8).count_ones() * weight;
+    }
+
+    res
+}